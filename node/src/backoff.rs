@@ -0,0 +1,123 @@
+//! Backing off block authoring while finality lags.
+//!
+//! When GRANDPA stalls, authors producing a block every slot grow an unfinalized chain without
+//! bound, which every node must keep and GRANDPA must eventually catch up with. Instead, once the
+//! unfinalized chain is longer than the slack, an author skips slots: it only authors when the
+//! slots elapsed since the best block exceed the excess length divided by the authoring bias, up
+//! to the maximum interval. Authoring resumes at its normal pace as soon as finality catches up.
+
+use node_template_runtime::BlockNumber;
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+use sc_consensus_slots::{BackoffAuthoringBlocksStrategy, BackoffAuthoringOnFinalizedHeadLagging};
+use sp_consensus_aura::Slot;
+
+/// Command line options of the authoring backoff.
+#[derive(Debug, Clone, clap::Args)]
+pub struct BackoffParams {
+	/// Keep authoring a block every slot however far finality lags behind.
+	#[arg(long = "no-authoring-backoff")]
+	pub disabled: bool,
+
+	/// The maximum number of slots skipped between two blocks while finality lags.
+	#[arg(long = "backoff-max-interval", value_name = "SLOTS", default_value_t = 100)]
+	pub max_interval: BlockNumber,
+
+	/// The number of unfinalized blocks tolerated before authoring backs off.
+	#[arg(long = "backoff-unfinalized-slack", value_name = "BLOCKS", default_value_t = 50)]
+	pub unfinalized_slack: BlockNumber,
+
+	/// The number of unfinalized blocks past the slack per skipped slot. The higher, the slower
+	/// authoring backs off.
+	#[arg(
+		long = "backoff-authoring-bias",
+		value_name = "BLOCKS",
+		default_value_t = 2,
+		value_parser = clap::value_parser!(u32).range(1..),
+	)]
+	pub authoring_bias: BlockNumber,
+}
+
+impl BackoffParams {
+	/// The backoff strategy of the authors, or `None` if it is disabled.
+	///
+	/// Its metrics are registered in `registry`, if any.
+	pub fn strategy(
+		&self,
+		registry: Option<&Registry>,
+	) -> Result<Option<AuthoringBackoff>, PrometheusError> {
+		if self.disabled {
+			return Ok(None)
+		}
+		Ok(Some(AuthoringBackoff {
+			inner: BackoffAuthoringOnFinalizedHeadLagging {
+				max_interval: self.max_interval,
+				unfinalized_slack: self.unfinalized_slack,
+				authoring_bias: self.authoring_bias,
+			},
+			metrics: registry.map(Metrics::register).transpose()?,
+		}))
+	}
+}
+
+/// The metrics of the authoring backoff.
+#[derive(Clone)]
+struct Metrics {
+	unfinalized_blocks: Gauge<U64>,
+	skipped_slots: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			unfinalized_blocks: register(
+				Gauge::new(
+					"authoring_backoff_unfinalized_blocks",
+					"Number of unfinalized blocks on the best chain at the last authoring slot",
+				)?,
+				registry,
+			)?,
+			skipped_slots: register(
+				Counter::new(
+					"authoring_backoff_skipped_slots_total",
+					"Number of authoring slots skipped because finality lags behind",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// [`BackoffAuthoringOnFinalizedHeadLagging`], reporting its decisions as metrics.
+#[derive(Clone)]
+pub struct AuthoringBackoff {
+	inner: BackoffAuthoringOnFinalizedHeadLagging<BlockNumber>,
+	metrics: Option<Metrics>,
+}
+
+impl BackoffAuthoringBlocksStrategy<BlockNumber> for AuthoringBackoff {
+	fn should_backoff(
+		&self,
+		chain_head_number: BlockNumber,
+		chain_head_slot: Slot,
+		finalized_number: BlockNumber,
+		slot_now: Slot,
+		logging_target: &str,
+	) -> bool {
+		let backoff = self.inner.should_backoff(
+			chain_head_number,
+			chain_head_slot,
+			finalized_number,
+			slot_now,
+			logging_target,
+		);
+		if let Some(metrics) = &self.metrics {
+			metrics
+				.unfinalized_blocks
+				.set(chain_head_number.saturating_sub(finalized_number).into());
+			if backoff {
+				metrics.skipped_slots.inc();
+			}
+		}
+		backoff
+	}
+}
@@ -45,6 +45,7 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for RemarkBuilder {
 			acc,
 			SystemCall::remark { remark: vec![] }.into(),
 			nonce,
+			None,
 		)
 		.into();
 
@@ -85,6 +86,7 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 			BalancesCall::transfer_keep_alive { dest: self.dest.clone().into(), value: self.value }
 				.into(),
 			nonce,
+			None,
 		)
 		.into();
 
@@ -94,12 +96,16 @@ impl frame_benchmarking_cli::ExtrinsicBuilder for TransferKeepAliveBuilder {
 
 /// Create a transaction using the given `call`.
 ///
+/// Fees are paid in the native currency, or in `fee_asset` if given. The asset must have a
+/// conversion rate in `pallet_asset_rate`.
+///
 /// Note: Should only be used for benchmarking.
 pub fn create_benchmark_extrinsic(
 	client: &FullClient,
 	sender: sp_core::sr25519::Pair,
 	call: runtime::RuntimeCall,
 	nonce: u32,
+	fee_asset: Option<runtime::AssetId>,
 ) -> runtime::UncheckedExtrinsic {
 	let genesis_hash = client.block_hash(0).ok().flatten().expect("Genesis block exists; qed");
 	let best_hash = client.chain_info().best_hash;
@@ -120,7 +126,7 @@ pub fn create_benchmark_extrinsic(
 		)),
 		frame_system::CheckNonce::<runtime::Runtime>::from(nonce),
 		frame_system::CheckWeight::<runtime::Runtime>::new(),
-		pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from(0),
+		pallet_asset_tx_payment::ChargeAssetTxPayment::<runtime::Runtime>::from(0, fee_asset),
 	);
 
 	let raw_payload = runtime::SignedPayload::from_raw(
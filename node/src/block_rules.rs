@@ -0,0 +1,221 @@
+//! Bad blocks and forced fork points, to recover from an incident without purging the database.
+//!
+//! The rules come from the `badBlocks` and `forkBlocks` extensions of the chain spec, completed by
+//! the `--bad-block` and `--fork-block` options of the node. A block breaks them if its hash is
+//! bad, or if its number is a fork point and its hash is not the one of the canonical chain.
+//!
+//! The import queue rejects the blocks breaking them and their children, and when the node starts,
+//! the unfinalized blocks breaking them are removed from the database with their descendants, so
+//! that the node follows the canonical chain again.
+
+use node_template_runtime::{opaque::Block, BlockNumber, Hash};
+use sc_client_api::{BadBlocks, ForkBlocks};
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sp_blockchain::{Backend as _, HeaderBackend};
+use sp_runtime::traits::Header as _;
+use std::{
+	collections::{HashMap, HashSet},
+	str::FromStr,
+	sync::Arc,
+};
+
+/// The log target of the block rules.
+const LOG_TARGET: &str = "node::block-rules";
+
+/// A block number that must have the given hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForkBlock {
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The hash of the block of the canonical chain at `number`.
+	pub hash: Hash,
+}
+
+impl FromStr for ForkBlock {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (number, hash) = s
+			.split_once('=')
+			.ok_or_else(|| format!("expected `<NUMBER>=<HASH>`, got `{s}`"))?;
+		Ok(Self {
+			number: number.parse().map_err(|e| format!("invalid block number: {e}"))?,
+			hash: hash.parse().map_err(|e| format!("invalid block hash: {e}"))?,
+		})
+	}
+}
+
+/// Command line additions to the block rules of the chain spec.
+#[derive(Debug, Clone, clap::Args)]
+pub struct BlockRulesParams {
+	/// The hash of a block to never import, nor its descendants. Can be repeated.
+	#[arg(long = "bad-block", value_name = "HASH")]
+	pub bad_blocks: Vec<Hash>,
+
+	/// A block number and the hash the block at this number must have, e.g. to pick the canonical
+	/// side of a fork. Can be repeated, and takes precedence over the fork blocks of the chain
+	/// spec.
+	#[arg(long = "fork-block", value_name = "NUMBER=HASH")]
+	pub fork_blocks: Vec<ForkBlock>,
+}
+
+/// The bad blocks and fork points the node enforces.
+#[derive(Debug, Clone, Default)]
+pub struct BlockRules {
+	bad: HashSet<Hash>,
+	forks: HashMap<BlockNumber, Hash>,
+}
+
+impl BlockRules {
+	/// The rules of `chain_spec`.
+	pub fn from_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+		let bad = crate::chain_spec::extension::<BadBlocks<Block>>(chain_spec);
+		let forks = crate::chain_spec::extension::<ForkBlocks<Block>>(chain_spec);
+		Self {
+			bad: bad.unwrap_or_default(),
+			forks: forks.unwrap_or_default().into_iter().collect(),
+		}
+	}
+
+	/// Add the rules of the command line options.
+	pub fn with_overrides(mut self, params: &BlockRulesParams) -> Self {
+		self.bad.extend(&params.bad_blocks);
+		self.forks
+			.extend(params.fork_blocks.iter().map(|fork| (fork.number, fork.hash)));
+		self
+	}
+
+	/// Whether there are no rules.
+	pub fn is_empty(&self) -> bool {
+		self.bad.is_empty() && self.forks.is_empty()
+	}
+
+	/// Whether the block `hash` at `number` breaks the rules.
+	pub fn is_bad(&self, number: BlockNumber, hash: Hash) -> bool {
+		self.bad.contains(&hash) || self.forks.get(&number).map_or(false, |fork| *fork != hash)
+	}
+
+	/// Remove the unfinalized blocks breaking the rules from the database, with their
+	/// descendants.
+	///
+	/// The best chain is reverted to the parent of its first bad block, GRANDPA authority set
+	/// changes included, and the other chains are removed leaf by leaf.
+	pub fn purge<B>(
+		&self,
+		client: Arc<crate::service::FullClient>,
+		backend: &B,
+	) -> sc_service::Result<()>
+	where
+		B: sc_client_api::Backend<Block>,
+	{
+		if self.is_empty() {
+			return Ok(())
+		}
+		let blockchain = backend.blockchain();
+
+		let info = blockchain.info();
+		if let Some(bad) = self.first_bad(blockchain, info.best_hash, info.finalized_number)? {
+			let blocks = info.best_number - bad + 1;
+			sc_consensus_grandpa::revert(client, blocks)?;
+			let (reverted, _) = backend.revert(blocks, false)?;
+			log::warn!(
+				target: LOG_TARGET,
+				"Reverted {reverted} blocks of the best chain, from #{bad} to #{}",
+				info.best_number,
+			);
+		}
+
+		let finalized_number = blockchain.info().finalized_number;
+		for leaf in blockchain.leaves()? {
+			let Some(bad) = self.first_bad(blockchain, leaf, finalized_number)? else { continue };
+			let mut hash = leaf;
+			loop {
+				let header = blockchain.expect_header(hash)?;
+				backend.remove_leaf_block(hash)?;
+				log::warn!(target: LOG_TARGET, "Removed block #{} ({hash:?})", header.number());
+				hash = *header.parent_hash();
+				// Ancestors shared with other leaves are removed with the last of them.
+				if *header.number() == bad || !blockchain.children(hash)?.is_empty() {
+					break
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// The number of the oldest unfinalized block breaking the rules in the chain ending at
+	/// `hash`, if any.
+	fn first_bad(
+		&self,
+		blockchain: &impl HeaderBackend<Block>,
+		mut hash: Hash,
+		finalized_number: BlockNumber,
+	) -> sp_blockchain::Result<Option<BlockNumber>> {
+		let mut first_bad = None;
+		loop {
+			let header = blockchain.expect_header(hash)?;
+			if *header.number() <= finalized_number {
+				return Ok(first_bad)
+			}
+			if self.is_bad(*header.number(), hash) {
+				first_bad = Some(*header.number());
+			}
+			hash = *header.parent_hash();
+		}
+	}
+}
+
+/// Block import rejecting the blocks breaking the [`BlockRules`], and their children, before
+/// passing the others to `I`.
+#[derive(Clone)]
+pub struct BlockRulesImport<I> {
+	inner: I,
+	rules: Arc<BlockRules>,
+}
+
+impl<I> BlockRulesImport<I> {
+	/// Wrap `inner` with `rules`.
+	pub fn new(inner: I, rules: BlockRules) -> Self {
+		Self { inner, rules: Arc::new(rules) }
+	}
+
+	/// Whether the block `hash` at `number`, or its parent, breaks the rules.
+	fn rejects(&self, number: BlockNumber, hash: Hash, parent_hash: Hash) -> bool {
+		let bad = self.rules.is_bad(number, hash) ||
+			number
+				.checked_sub(1)
+				.map_or(false, |parent| self.rules.is_bad(parent, parent_hash));
+		if bad {
+			log::warn!(target: LOG_TARGET, "Rejected bad block #{number} ({hash:?})");
+		}
+		bad
+	}
+}
+
+#[async_trait::async_trait]
+impl<I> BlockImport<Block> for BlockRulesImport<I>
+where
+	I: BlockImport<Block> + Send + Sync,
+{
+	type Error = I::Error;
+
+	async fn check_block(
+		&mut self,
+		block: BlockCheckParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		if self.rejects(block.number, block.hash, block.parent_hash) {
+			return Ok(ImportResult::KnownBad)
+		}
+		self.inner.check_block(block).await
+	}
+
+	async fn import_block(
+		&mut self,
+		block: BlockImportParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		if self.rejects(*block.header.number(), block.post_hash(), *block.header.parent_hash()) {
+			return Ok(ImportResult::KnownBad)
+		}
+		self.inner.import_block(block).await
+	}
+}
@@ -1,15 +1,147 @@
-use node_template_runtime::{AccountId, RuntimeGenesisConfig, Signature, WASM_BINARY};
-use sc_service::ChainType;
+use crate::grandpa::GrandpaSettings;
+use codec::{Decode, Encode};
+use node_template_runtime::{
+	genesis_config_presets, opaque::Block, AccountId, BlockNumber, Hash, RuntimeGenesisConfig,
+	Signature, WASM_BINARY,
+};
+use node_template_runtime_api::{PresetChain, PresetChainType};
+use sc_chain_spec::ChainSpecExtension;
+use sc_client_api::{BadBlocks, ForkBlocks};
+use sc_consensus_grandpa::SharedAuthoritySet;
+use sc_executor::WasmExecutor;
+use sc_network::config::MultiaddrWithPeerId;
+use sc_service::{ChainType, Configuration, Role};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{
+	sr25519,
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+	Bytes, Pair, Public,
+};
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_state_machine::BasicExternalities;
 
 // The URL for the telemetry server.
 // const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// The settings of the node shipped in the chain spec, next to the genesis config.
+///
+/// Missing fields take their default value, so chain specs without extensions remain valid.
+/// Each field has its own type, which [`extension`] retrieves it by.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Extensions {
+	/// The blocks that are never imported, nor any of their descendants.
+	pub bad_blocks: BadBlocks<Block>,
+	/// The hashes the blocks at these numbers must have, to pick a side of a fork.
+	pub fork_blocks: ForkBlocks<Block>,
+	/// The finalized state light clients start syncing from, written by
+	/// `build-spec --light-sync-state`.
+	pub light_sync_state: Option<LightSyncState>,
+	/// The boot nodes recommended to nodes of each role, on top of the `bootNodes` of all nodes.
+	pub role_boot_nodes: RoleBootNodes,
+	/// Which nodes report to the `telemetryEndpoints` by default.
+	pub telemetry: TelemetryDefaults,
+	/// The settings of GRANDPA finality.
+	pub grandpa: GrandpaSettings,
+}
+
+/// The extension of type `T` of `chain_spec`, or its default if it has none.
+pub fn extension<T: Default + Clone + 'static>(chain_spec: &dyn sc_service::ChainSpec) -> T {
+	sc_chain_spec::get_extension::<T>(chain_spec.extensions())
+		.cloned()
+		.unwrap_or_default()
+}
+
+/// The finalized block light clients start syncing from, with its GRANDPA authority set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LightSyncState {
+	/// The SCALE-encoded header of the block.
+	pub finalized_block_header: Bytes,
+	/// The SCALE-encoded GRANDPA authority set at the block.
+	pub grandpa_authority_set: Bytes,
+}
+
+impl LightSyncState {
+	/// The state of the last block finalized by `client`.
+	pub fn finalized<C: HeaderBackend<Block>>(
+		client: &C,
+		authority_set: &SharedAuthoritySet<Hash, BlockNumber>,
+	) -> sc_cli::Result<Self> {
+		let header = client.expect_header(client.info().finalized_hash)?;
+		Ok(Self {
+			finalized_block_header: header.encode().into(),
+			grandpa_authority_set: authority_set.clone_inner().encode().into(),
+		})
+	}
+
+	/// Set as the light sync state of `chain_spec`.
+	pub fn set(self, chain_spec: &mut dyn sc_service::ChainSpec) -> Result<(), String> {
+		let extension =
+			sc_chain_spec::get_extension_mut::<Option<Self>>(chain_spec.extensions_mut())
+				.ok_or("the chain spec has no light sync state extension")?;
+		*extension = Some(self);
+		Ok(())
+	}
+}
+
+/// The boot nodes recommended to nodes of each role.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RoleBootNodes {
+	/// The boot nodes of authorities, e.g. sentries or other authorities.
+	pub authority: Vec<MultiaddrWithPeerId>,
+	/// The boot nodes of full nodes, e.g. public RPC nodes.
+	pub full: Vec<MultiaddrWithPeerId>,
+}
+
+impl RoleBootNodes {
+	/// Add the boot nodes of the role of the node to its network configuration.
+	pub fn apply(&self, config: &mut Configuration) {
+		let boot_nodes = if config.role.is_authority() { &self.authority } else { &self.full };
+		for boot_node in boot_nodes {
+			if !config.network.boot_nodes.contains(boot_node) {
+				config.network.boot_nodes.push(boot_node.clone());
+			}
+		}
+	}
+}
+
+/// Which nodes report to the telemetry endpoints of the chain spec by default.
+///
+/// Nodes still report to the endpoints given with `--telemetry-url`, and never with
+/// `--no-telemetry`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct TelemetryDefaults {
+	/// Whether authorities report.
+	pub authorities: bool,
+	/// Whether full nodes report.
+	pub full_nodes: bool,
+}
+
+impl Default for TelemetryDefaults {
+	fn default() -> Self {
+		Self { authorities: true, full_nodes: true }
+	}
+}
+
+impl TelemetryDefaults {
+	/// Whether nodes of `role` report by default.
+	pub fn enabled_for(&self, role: &Role) -> bool {
+		if role.is_authority() {
+			self.authorities
+		} else {
+			self.full_nodes
+		}
+	}
+}
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -28,90 +160,88 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Generate a validator account together with its Aura and GRANDPA authority keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
+	(
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<AuraId>(s),
+		get_from_seed::<GrandpaId>(s),
+	)
+}
+
+/// Build a chain spec whose genesis config is the runtime's preset called `preset`.
+///
+/// The preset, and the name, id and type of its chain, are read from the Wasm runtime of the node
+/// through its `GenesisPresetsApi`, as any other tool would. See [`genesis_config_presets`] for
+/// the available presets.
+pub fn preset_config(preset: &str) -> Result<ChainSpec, String> {
+	let code = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
+	let runtime = WasmRuntime::new(code);
+
+	let Some(chain) =
+		runtime.call::<Option<PresetChain>>("GenesisPresetsApi_preset_chain", preset.as_bytes())?
+	else {
+		let names = runtime.call::<Vec<Vec<u8>>>("GenesisPresetsApi_preset_names", ())?;
+		let names = names.iter().map(|name| String::from_utf8_lossy(name)).collect::<Vec<_>>();
+		return Err(format!(
+			"Unknown genesis preset `{preset}`, expected one of: {}",
+			names.join(", ")
+		))
+	};
+	let patch = runtime
+		.call::<Option<Vec<u8>>>("GenesisPresetsApi_get_preset", preset.as_bytes())?
+		.ok_or_else(|| format!("Genesis preset `{preset}` is not provided by the runtime"))?;
+
+	Ok(ChainSpec::builder(code, Extensions::default())
+		.with_name(&String::from_utf8_lossy(&chain.name))
+		.with_id(&String::from_utf8_lossy(&chain.id))
+		.with_chain_type(match chain.chain_type {
+			PresetChainType::Development => ChainType::Development,
+			PresetChainType::Local => ChainType::Local,
+			PresetChainType::Live => ChainType::Live,
+		})
+		.with_genesis_config_patch(serde_json::from_slice(&patch).map_err(|e| e.to_string())?)
+		.build())
+}
+
+/// A Wasm runtime, called without any state.
+struct WasmRuntime<'a> {
+	code: &'a [u8],
+	executor: WasmExecutor<sp_io::SubstrateHostFunctions>,
+}
+
+impl<'a> WasmRuntime<'a> {
+	fn new(code: &'a [u8]) -> Self {
+		Self { code, executor: WasmExecutor::builder().build() }
+	}
+
+	/// Call `method` of the runtime with `args`, decoding its result as `R`.
+	fn call<R: Decode>(&self, method: &str, args: impl Encode) -> Result<R, String> {
+		let code_fetcher = WrappedRuntimeCode(self.code.into());
+		let runtime_code = RuntimeCode {
+			code_fetcher: &code_fetcher,
+			heap_pages: None,
+			hash: sp_core::blake2_256(self.code).to_vec(),
+		};
+		let result = self
+			.executor
+			.call(
+				&mut BasicExternalities::new_empty(),
+				&runtime_code,
+				method,
+				&args.encode(),
+				CallContext::Offchain,
+			)
+			.0
+			.map_err(|e| format!("{method} failed: {e}"))?;
+		R::decode(&mut &result[..]).map_err(|e| format!("invalid result of {method}: {e}"))
+	}
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
-		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
-		None,
-	)
-	.with_name("Development")
-	.with_id("dev")
-	.with_chain_type(ChainType::Development)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-		],
-		true,
-	))
-	.build())
+	preset_config(genesis_config_presets::DEVELOPMENT)
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
-	Ok(ChainSpec::builder(
-		WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
-		None,
-	)
-	.with_name("Local Testnet")
-	.with_id("local_testnet")
-	.with_chain_type(ChainType::Local)
-	.with_genesis_config_patch(testnet_genesis(
-		// Initial PoA authorities
-		vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-		// Sudo account
-		get_account_id_from_seed::<sr25519::Public>("Alice"),
-		// Pre-funded accounts
-		vec![
-			get_account_id_from_seed::<sr25519::Public>("Alice"),
-			get_account_id_from_seed::<sr25519::Public>("Bob"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie"),
-			get_account_id_from_seed::<sr25519::Public>("Dave"),
-			get_account_id_from_seed::<sr25519::Public>("Eve"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie"),
-			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Charlie//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
-			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-		],
-		true,
-	))
-	.build())
-}
-
-/// Configure initial storage state for FRAME modules.
-fn testnet_genesis(
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
-) -> serde_json::Value {
-	serde_json::json!({
-		"balances": {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			"balances": endowed_accounts.iter().cloned().map(|k| (k, 1u64 << 60)).collect::<Vec<_>>(),
-		},
-		"aura": {
-			"authorities": initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
-		},
-		"grandpa": {
-			"authorities": initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
-		},
-		"sudo": {
-			// Assign network admin rights.
-			"key": Some(root_key),
-		},
-	})
+	preset_config(genesis_config_presets::LOCAL_TESTNET)
 }
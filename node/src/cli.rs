@@ -1,4 +1,39 @@
+use crate::{backoff::BackoffParams, block_rules::BlockRulesParams, grandpa::GrandpaParams};
 use sc_cli::RunCmd;
+use std::{path::PathBuf, str::FromStr};
+
+/// Block authoring mode used in place of Aura and GRANDPA for local development.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal blocks only when requested through the `engine_createBlock` RPC.
+	Manual,
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"manual" => Self::Manual,
+			"instant" => Self::Instant,
+			s => {
+				let millis = s
+					.strip_prefix("interval=")
+					.ok_or_else(|| format!("unknown sealing mode `{s}`"))?
+					.parse::<u64>()
+					.map_err(|e| format!("invalid sealing interval: {e}"))?;
+				if millis == 0 {
+					return Err("sealing interval must be greater than zero".into())
+				}
+				Self::Interval(millis)
+			},
+		})
+	}
+}
 
 #[derive(Debug, clap::Parser)]
 pub struct Cli {
@@ -7,6 +42,52 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Replace Aura and GRANDPA with manual sealing and instant finality.
+	///
+	/// Accepted values are `manual`, `instant` and `interval=<ms>`. Blocks can always be
+	/// produced and finalized on demand through the `engine_createBlock` and
+	/// `engine_finalizeBlock` RPC methods.
+	#[arg(long, value_name = "MODE")]
+	pub sealing: Option<Sealing>,
+
+	/// Serve the decimal `u32` in this file to the offchain worker of the template pallet.
+	///
+	/// The file is read on every new best block, and its value written to the `template::value`
+	/// key of the persistent offchain storage, replacing the one set there.
+	#[arg(long, value_name = "PATH")]
+	pub offchain_value_file: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub grandpa: GrandpaParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub backoff: BackoffParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub block_rules: BlockRulesParams,
+}
+
+/// `build-spec`, optionally starting from one of the runtime's genesis config presets.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct BuildSpecCmd {
+	#[clap(flatten)]
+	pub inner: sc_cli::BuildSpecCmd,
+
+	/// Build the chain spec from the runtime's genesis config preset with this name instead of
+	/// `--chain`.
+	///
+	/// The runtime provides `development`, `local_testnet` and `staging`.
+	#[arg(long, value_name = "NAME")]
+	pub preset: Option<String>,
+
+	/// Add the state of the last finalized block of the local database to the chain spec, for
+	/// light clients to start syncing from.
+	#[arg(long, conflicts_with = "preset")]
+	pub light_sync_state: bool,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -17,7 +98,7 @@ pub enum Subcommand {
 	Key(sc_cli::KeySubcommand),
 
 	/// Build a chain specification.
-	BuildSpec(sc_cli::BuildSpecCmd),
+	BuildSpec(BuildSpecCmd),
 
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
@@ -28,6 +109,10 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the state of a given block, and optionally the blocks following it, into a compact
+	/// snapshot file.
+	ExportSnapshot(crate::snapshot::ExportSnapshotCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -41,11 +126,38 @@ pub enum Subcommand {
 	#[command(subcommand)]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
-	/// Try-runtime has migrated to a standalone CLI
-	/// (<https://github.com/paritytech/try-runtime-cli>). The subcommand exists as a stub and
-	/// deprecation notice. It will be removed entirely some time after January 2024.
+	/// Run the migrations of this node's runtime against a state snapshot, with their
+	/// `pre_upgrade` and `post_upgrade` checks.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(crate::try_runtime::TryRuntimeCmd),
+
+	/// Run the migrations of this node's runtime against a state snapshot. Requires the node to
+	/// be built with `--features try-runtime`.
+	#[cfg(not(feature = "try-runtime"))]
 	TryRuntime,
 
+	/// Replay a snapshot written by `export-snapshot` with a candidate runtime, running its
+	/// migrations and the blocks of the snapshot.
+	#[cfg(feature = "try-runtime")]
+	RehearseUpgrade(crate::rehearse_upgrade::RehearseUpgradeCmd),
+
+	/// Replay a snapshot written by `export-snapshot` with a candidate runtime. Requires the node
+	/// to be built with `--features try-runtime`.
+	#[cfg(not(feature = "try-runtime"))]
+	RehearseUpgrade,
+
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Build a validated chain spec from a TOML or YAML description of a network.
+	GenerateSpec(crate::generate_spec::GenerateSpecCmd),
+
+	/// Run a local testnet of several validator nodes with dev keys.
+	LocalTestnet(crate::local_testnet::LocalTestnetCmd),
+
+	/// Build the calls scheduling a runtime upgrade from a Wasm file.
+	ScheduleUpgrade(crate::schedule_upgrade::ScheduleUpgradeCmd),
+
+	/// Show the vesting balances of an account.
+	VestingInfo(crate::vesting_info::VestingInfoCmd),
 }
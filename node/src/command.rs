@@ -1,6 +1,6 @@
 use crate::{
 	benchmarking::{inherent_benchmark_data, RemarkBuilder, TransferKeepAliveBuilder},
-	chain_spec,
+	chain_spec::{self, TelemetryDefaults},
 	cli::{Cli, Subcommand},
 	service,
 };
@@ -53,8 +53,27 @@ pub fn run() -> sc_cli::Result<()> {
 	match &cli.subcommand {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
 		Some(Subcommand::BuildSpec(cmd)) => {
-			let runner = cli.create_runner(cmd)?;
-			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+			let runner = cli.create_runner(&cmd.inner)?;
+			runner.sync_run(|config| {
+				let light_sync_state = if cmd.light_sync_state {
+					let PartialComponents { client, other: (_, grandpa_link, _), .. } =
+						service::new_partial(&config)?;
+					Some(chain_spec::LightSyncState::finalized(
+						&*client,
+						grandpa_link.shared_authority_set(),
+					)?)
+				} else {
+					None
+				};
+				let mut chain_spec: Box<dyn sc_service::ChainSpec> = match &cmd.preset {
+					Some(preset) => Box::new(chain_spec::preset_config(preset)?),
+					None => config.chain_spec,
+				};
+				if let Some(light_sync_state) = light_sync_state {
+					light_sync_state.set(&mut *chain_spec)?;
+				}
+				cmd.inner.run(chain_spec, config.network)
+			})
 		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -78,6 +97,13 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ExportSnapshot(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				cmd.run(&*client)
+			})
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -171,19 +197,41 @@ pub fn run() -> sc_cli::Result<()> {
 			})
 		},
 		#[cfg(feature = "try-runtime")]
-		Some(Subcommand::TryRuntime) => Err(try_runtime_cli::DEPRECATION_NOTICE.into()),
+		Some(Subcommand::TryRuntime(cmd)) => cmd.run(),
 		#[cfg(not(feature = "try-runtime"))]
 		Some(Subcommand::TryRuntime) => Err("TryRuntime wasn't enabled when building the node. \
 				You can enable it with `--features try-runtime`."
 			.into()),
+		#[cfg(feature = "try-runtime")]
+		Some(Subcommand::RehearseUpgrade(cmd)) => cmd.run(),
+		#[cfg(not(feature = "try-runtime"))]
+		Some(Subcommand::RehearseUpgrade) => Err("RehearseUpgrade wasn't enabled when building \
+				the node. You can enable it with `--features try-runtime`."
+			.into()),
 		Some(Subcommand::ChainInfo(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run::<Block>(&config))
 		},
+		Some(Subcommand::GenerateSpec(cmd)) => cmd.run(),
+		Some(Subcommand::LocalTestnet(cmd)) => cmd.run(&cli),
+		Some(Subcommand::ScheduleUpgrade(cmd)) => cmd.run(),
+		Some(Subcommand::VestingInfo(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = service::new_partial(&config)?;
+				cmd.run(&*client)
+			})
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+			let options = service::NodeOptions::from_cli(&cli);
+			let telemetry_url = !cli.run.telemetry_params.telemetry_endpoints.is_empty();
+			runner.run_node_until_exit(|mut config| async move {
+				let telemetry = chain_spec::extension::<TelemetryDefaults>(&*config.chain_spec);
+				if !telemetry_url && !telemetry.enabled_for(&config.role) {
+					config.telemetry_endpoints = None;
+				}
+				service::new_full(config, options).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
@@ -0,0 +1,103 @@
+//! Data the node provides to the runtime, on top of what Substrate provides.
+//!
+//! The node can feed the offchain workers with data that is local to it, instead of fetched from
+//! the network, by writing it to their persistent offchain storage. Unlike a custom host function,
+//! this needs nothing from the runtime, which keeps working with any node.
+//!
+//! The values are written by their own task, on every new best block, alongside the offchain
+//! workers that `sc_offchain` starts for the same blocks: a worker sees a new value at the latest
+//! at the next block.
+
+use crate::cli::Cli;
+use codec::Encode;
+use futures::StreamExt;
+use node_template_runtime::{opaque::Block, TemplateValueKey};
+use sc_client_api::BlockchainEvents;
+use sp_core::offchain::OffchainStorage;
+use std::path::Path;
+
+/// The log target of the extensions.
+const LOG_TARGET: &str = "node::extensions";
+
+/// The value to write under a key of the persistent offchain storage, if any.
+type OffchainStorageSource = (Vec<u8>, Box<dyn Fn() -> Option<Vec<u8>> + Send + Sync>);
+
+/// The data provided by the node, part of [`crate::service::NodeOptions`].
+#[derive(Default)]
+pub struct NodeExtensions {
+	offchain_storage: Vec<OffchainStorageSource>,
+}
+
+impl NodeExtensions {
+	/// The extensions enabled by the command line options.
+	pub fn from_cli(cli: &Cli) -> Self {
+		let mut extensions = Self::default();
+		if let Some(path) = cli.offchain_value_file.clone() {
+			extensions = extensions
+				.with_offchain_storage_value(TemplateValueKey::get(), move || {
+					read_value(&path).map(|value| value.encode())
+				});
+		}
+		extensions
+	}
+
+	/// Write the value of `source` under `key` in the persistent offchain storage on every new
+	/// best block, leaving the stored value as it is when `source` has none.
+	pub fn with_offchain_storage_value(
+		mut self,
+		key: &[u8],
+		source: impl Fn() -> Option<Vec<u8>> + Send + Sync + 'static,
+	) -> Self {
+		self.offchain_storage.push((key.to_vec(), Box::new(source)));
+		self
+	}
+
+	/// The task writing the offchain storage values to `storage` at startup and on every new best
+	/// block imported by `client`, or `None` if there are no values.
+	pub(crate) fn offchain_storage_task<C, S>(
+		self,
+		client: &C,
+		mut storage: S,
+	) -> Option<impl std::future::Future<Output = ()> + Send + 'static>
+	where
+		C: BlockchainEvents<Block>,
+		S: OffchainStorage + 'static,
+	{
+		if self.offchain_storage.is_empty() {
+			return None
+		}
+		let mut imports = client.import_notification_stream();
+		Some(async move {
+			let mut write = || {
+				for (key, source) in &self.offchain_storage {
+					if let Some(value) = source() {
+						storage.set(sp_offchain::STORAGE_PREFIX, key, &value);
+					}
+				}
+			};
+			write();
+			while let Some(notification) = imports.next().await {
+				if notification.is_new_best {
+					write();
+				}
+			}
+		})
+	}
+}
+
+/// The value in the file at `path`, if it exists and is valid.
+fn read_value(path: &Path) -> Option<u32> {
+	let content = match std::fs::read_to_string(path) {
+		Ok(content) => content,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+		Err(e) => {
+			log::warn!(target: LOG_TARGET, "Cannot read {}: {e}", path.display());
+			return None
+		},
+	};
+	content
+		.trim()
+		.parse()
+		.map_err(|e| log::warn!(target: LOG_TARGET, "Invalid value in {}: {e}", path.display()))
+		.ok()
+}
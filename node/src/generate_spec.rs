@@ -0,0 +1,361 @@
+//! The `generate-spec` subcommand: build the chain spec of a network from a description of it.
+//!
+//! The description is a TOML or YAML file, picked by its extension:
+//!
+//! ```toml
+//! name = "Example Network"
+//! id = "example"
+//! chain_type = "live"
+//! sudo = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! boot_nodes = ["/dns/boot-0.example.com/tcp/30333/p2p/12D3KooW…"]
+//!
+//! [token]
+//! symbol = "EXM"
+//! decimals = 12
+//!
+//! [[authorities]]
+//! account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! aura = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! grandpa = "0x88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee"
+//!
+//! [[endowed]]
+//! account = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+//! balance = "1000000000000000"
+//!
+//! [template]
+//! something = 42
+//! ```
+//!
+//! Keys are SS58 addresses or hex-encoded public keys: sr25519 for accounts and Aura, ed25519 for
+//! GRANDPA. Balances are integers or decimal strings, for those beyond the range of TOML integers.
+//!
+//! The description is validated before anything is written, and the genesis state is built with
+//! the runtime of this node, so that the chain spec is known to start a chain.
+
+use crate::chain_spec::{ChainSpec, Extensions};
+use node_template_runtime::{
+	opaque::SessionKeys, AccountId, AuraId, Balance, GrandpaId, MaxValidators, TreasuryAccount,
+	EXISTENTIAL_DEPOSIT, WASM_BINARY,
+};
+use sc_network::config::MultiaddrWithPeerId;
+use sc_service::{ChainType, Properties};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use sp_core::{bytes::to_hex, crypto::Ss58Codec, ed25519, sr25519};
+use sp_runtime::BuildStorage;
+use std::{
+	collections::BTreeSet,
+	path::{Path, PathBuf},
+};
+
+/// Build a chain spec from a TOML or YAML description of a network.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct GenerateSpecCmd {
+	/// Path to the description of the network, with a `.toml`, `.yaml` or `.yml` extension.
+	#[arg(value_name = "PATH")]
+	pub description: PathBuf,
+
+	/// Write the chain spec in raw format, i.e. with the genesis state instead of the genesis
+	/// config.
+	#[arg(long)]
+	pub raw: bool,
+
+	/// Path to write the chain spec to. Defaults to the standard output.
+	#[arg(long, short, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+}
+
+impl GenerateSpecCmd {
+	/// Write the chain spec, failing if the description is invalid.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let description = NetworkDescription::load(&self.description)?;
+		description.validate()?;
+
+		let chain_spec = description.chain_spec()?;
+		chain_spec.build_storage().map_err(|e| format!("invalid genesis: {e}"))?;
+		let json = chain_spec.as_json(self.raw)?;
+		match &self.output {
+			Some(path) => std::fs::write(path, json)?,
+			None => println!("{json}"),
+		}
+		Ok(())
+	}
+}
+
+/// The description of a network.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkDescription {
+	/// The human-readable name of the network.
+	pub name: String,
+	/// The identifier of the network, e.g. naming its directory in the base path of nodes.
+	pub id: String,
+	/// The type of the network.
+	pub chain_type: NetworkType,
+	/// The network protocol id, if different from the default one.
+	#[serde(default)]
+	pub protocol_id: Option<String>,
+	/// The validators of the first session.
+	pub authorities: Vec<Authority>,
+	/// The sudo key, if any.
+	#[serde(default)]
+	pub sudo: Option<PublicKey>,
+	/// The accounts funded at genesis.
+	#[serde(default)]
+	pub endowed: Vec<Endowment>,
+	/// The token of the network, shown by wallets.
+	pub token: Token,
+	/// The boot nodes of all nodes.
+	#[serde(default)]
+	pub boot_nodes: Vec<MultiaddrWithPeerId>,
+	/// The genesis config of the template pallet.
+	#[serde(default)]
+	pub template: TemplateGenesis,
+}
+
+/// The type of a network.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkType {
+	/// A development chain, run by a single node.
+	Development,
+	/// A local testnet.
+	Local,
+	/// A long-lived network.
+	Live,
+}
+
+/// A validator of the first session and its session keys.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Authority {
+	/// The sr25519 account of the validator.
+	pub account: PublicKey,
+	/// The sr25519 Aura key of the validator.
+	pub aura: PublicKey,
+	/// The ed25519 GRANDPA key of the validator.
+	pub grandpa: PublicKey,
+}
+
+/// An account funded at genesis.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowment {
+	/// The sr25519 account.
+	pub account: PublicKey,
+	/// Its free balance, at least the existential deposit.
+	#[serde(deserialize_with = "deserialize_balance")]
+	pub balance: Balance,
+}
+
+/// The token of a network.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Token {
+	/// The symbol of the token.
+	pub symbol: String,
+	/// The number of decimals of the token.
+	pub decimals: u8,
+}
+
+/// The genesis config of the template pallet.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateGenesis {
+	/// The initial value of `Something`, if any.
+	#[serde(default)]
+	pub something: Option<u32>,
+}
+
+/// A 32-byte public key, given as an SS58 address or in hex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PublicKey(pub [u8; 32]);
+
+impl PublicKey {
+	fn account(&self) -> AccountId {
+		self.0.into()
+	}
+
+	fn aura(&self) -> AuraId {
+		sr25519::Public::from_raw(self.0).into()
+	}
+
+	fn grandpa(&self) -> GrandpaId {
+		ed25519::Public::from_raw(self.0).into()
+	}
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let key = String::deserialize(deserializer)?;
+		let bytes = if key.starts_with("0x") {
+			sp_core::bytes::from_hex(&key)
+				.ok()
+				.and_then(|bytes| bytes.try_into().ok())
+				.ok_or_else(|| D::Error::custom(format!("`{key}` is not a 32-byte hex key")))?
+		} else {
+			sr25519::Public::from_ss58check(&key)
+				.map_err(|e| D::Error::custom(format!("`{key}` is not an SS58 address: {e:?}")))?
+				.0
+		};
+		Ok(Self(bytes))
+	}
+}
+
+/// A balance given as an integer or a decimal string.
+fn deserialize_balance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Amount {
+		Integer(u64),
+		Decimal(String),
+	}
+
+	match Amount::deserialize(deserializer)? {
+		Amount::Integer(balance) => Ok(balance.into()),
+		Amount::Decimal(balance) => balance
+			.parse()
+			.map_err(|e| D::Error::custom(format!("invalid balance `{balance}`: {e}"))),
+	}
+}
+
+impl NetworkDescription {
+	/// Read the description in the file at `path`, in the format given by its extension.
+	pub fn load(path: &Path) -> sc_cli::Result<Self> {
+		let content = std::fs::read_to_string(path)?;
+		let invalid = |e: String| sc_cli::Error::Input(format!("{}: {e}", path.display()));
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => toml::from_str(&content).map_err(|e| invalid(e.to_string())),
+			Some("yaml" | "yml") =>
+				serde_yaml::from_str(&content).map_err(|e| invalid(e.to_string())),
+			_ => Err(invalid("expected a `.toml`, `.yaml` or `.yml` file".into())),
+		}
+	}
+
+	/// Check that the description makes a valid genesis.
+	pub fn validate(&self) -> Result<(), String> {
+		if self.name.is_empty() || self.id.is_empty() {
+			return Err("the name and the id of the network must not be empty".into())
+		}
+		if self.authorities.is_empty() {
+			return Err("at least one authority is required".into())
+		}
+		if self.authorities.len() > MaxValidators::get() as usize {
+			return Err(format!(
+				"{} authorities are given, the runtime allows at most {}",
+				self.authorities.len(),
+				MaxValidators::get(),
+			))
+		}
+
+		let mut accounts = BTreeSet::new();
+		let mut keys = BTreeSet::new();
+		for authority in &self.authorities {
+			if !accounts.insert(authority.account) {
+				return Err(format!("duplicate authority account {}", ss58(&authority.account)))
+			}
+			if !keys.insert(("aura", authority.aura)) {
+				return Err(format!("duplicate Aura key {}", ss58(&authority.aura)))
+			}
+			if !keys.insert(("grandpa", authority.grandpa)) {
+				return Err(format!("duplicate GRANDPA key {}", to_hex(&authority.grandpa.0, false)))
+			}
+		}
+
+		let mut endowed = BTreeSet::new();
+		for endowment in &self.endowed {
+			if !endowed.insert(endowment.account) {
+				return Err(format!("duplicate endowed account {}", ss58(&endowment.account)))
+			}
+			if endowment.balance < EXISTENTIAL_DEPOSIT {
+				return Err(format!(
+					"the balance of {} is {}, below the existential deposit of {EXISTENTIAL_DEPOSIT}",
+					ss58(&endowment.account),
+					endowment.balance,
+				))
+			}
+		}
+		Ok(())
+	}
+
+	/// The chain spec of the network.
+	pub fn chain_spec(&self) -> Result<ChainSpec, String> {
+		let mut properties = Properties::new();
+		properties.insert("tokenSymbol".into(), self.token.symbol.clone().into());
+		properties.insert("tokenDecimals".into(), self.token.decimals.into());
+
+		let chain_type = match self.chain_type {
+			NetworkType::Development => ChainType::Development,
+			NetworkType::Local => ChainType::Local,
+			NetworkType::Live => ChainType::Live,
+		};
+		let mut builder = ChainSpec::builder(
+			WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+			Extensions::default(),
+		)
+		.with_name(&self.name)
+		.with_id(&self.id)
+		.with_chain_type(chain_type)
+		.with_properties(properties)
+		.with_boot_nodes(self.boot_nodes.clone())
+		.with_genesis_config_patch(self.genesis_patch());
+		if let Some(protocol_id) = &self.protocol_id {
+			builder = builder.with_protocol_id(protocol_id);
+		}
+		Ok(builder.build())
+	}
+
+	/// The genesis config patch of the network, as the runtime's presets write it.
+	/// The endowed accounts, and the treasury with the existential deposit unless it is endowed,
+	/// so that fee shares below the existential deposit are not burnt.
+	fn balances(&self) -> Vec<(AccountId, Balance)> {
+		let mut balances: Vec<_> = self
+			.endowed
+			.iter()
+			.map(|endowment| (endowment.account.account(), endowment.balance))
+			.collect();
+		let treasury = TreasuryAccount::get();
+		if !balances.iter().any(|(account, _)| *account == treasury) {
+			balances.push((treasury, EXISTENTIAL_DEPOSIT));
+		}
+		balances
+	}
+
+	fn genesis_patch(&self) -> serde_json::Value {
+		serde_json::json!({
+			"balances": {
+				"balances": self.balances(),
+			},
+			"validatorSet": {
+				"initialValidators": self
+					.authorities
+					.iter()
+					.map(|authority| authority.account.account())
+					.collect::<Vec<_>>(),
+			},
+			"session": {
+				"keys": self
+					.authorities
+					.iter()
+					.map(|authority| {
+						let keys = SessionKeys {
+							aura: authority.aura.aura(),
+							grandpa: authority.grandpa.grandpa(),
+						};
+						(authority.account.account(), authority.account.account(), keys)
+					})
+					.collect::<Vec<_>>(),
+			},
+			"sudo": {
+				"key": self.sudo.map(|sudo| sudo.account()),
+			},
+			"templateModule": {
+				"something": self.template.something,
+			},
+		})
+	}
+}
+
+/// `key` as an SS58 address, for error messages.
+fn ss58(key: &PublicKey) -> String {
+	sr25519::Public::from_raw(key.0).to_ss58check()
+}
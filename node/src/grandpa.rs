@@ -0,0 +1,152 @@
+//! The settings of GRANDPA finality.
+//!
+//! They are read from the `grandpa` extension of the chain spec, so that each network ships its
+//! own, and each of them can be overridden by a command line option of the node.
+
+use node_template_runtime::opaque::Block;
+use sc_consensus_grandpa::{
+	BeforeBestBlockBy, ThreeQuartersOfTheUnfinalizedChain, VotingRulesBuilder,
+};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use std::{
+	num::{NonZeroU32, NonZeroU64},
+	str::FromStr,
+	time::Duration,
+};
+
+/// The default minimum period of blocks on which justifications will be imported and generated.
+pub const GRANDPA_JUSTIFICATION_PERIOD: NonZeroU32 = NonZeroU32::new(512).expect("512 is not zero");
+
+/// A rule restricting the blocks GRANDPA votes for.
+///
+/// In the chain spec, `{"beforeBestBlockBy": N}` or `"threeQuartersOfTheUnfinalizedChain"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VotingRule {
+	/// Vote no further than the best block minus this many blocks.
+	BeforeBestBlockBy(u32),
+	/// Vote no further than three quarters of the way from the last finalized block to the best
+	/// block.
+	ThreeQuartersOfTheUnfinalizedChain,
+}
+
+impl FromStr for VotingRule {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"three-quarters" => Self::ThreeQuartersOfTheUnfinalizedChain,
+			s => {
+				let blocks = s
+					.strip_prefix("best-minus=")
+					.ok_or_else(|| format!("unknown voting rule `{s}`"))?
+					.parse::<u32>()
+					.map_err(|e| format!("invalid number of blocks: {e}"))?;
+				Self::BeforeBestBlockBy(blocks)
+			},
+		})
+	}
+}
+
+/// The `grandpa` extension of the chain spec.
+///
+/// Missing fields take their default value, which is also the behavior of chain specs without
+/// the extension. The gossip duration and justification period cannot be zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct GrandpaSettings {
+	/// The interval at which votes are gossiped, in milliseconds.
+	pub gossip_duration: NonZeroU64,
+	/// The minimum period of blocks on which justifications are imported and generated.
+	pub justification_period: NonZeroU32,
+	/// Whether non-authorities of the network run the GRANDPA observer protocol instead of the
+	/// full voter, which changes how votes are gossiped to them.
+	pub observer_enabled: bool,
+	/// The rules restricting the blocks voted for, all of them applying.
+	pub voting_rules: Vec<VotingRule>,
+}
+
+impl Default for GrandpaSettings {
+	fn default() -> Self {
+		Self {
+			gossip_duration: NonZeroU64::new(333).expect("333 is not zero"),
+			justification_period: GRANDPA_JUSTIFICATION_PERIOD,
+			observer_enabled: false,
+			// The rules of `VotingRulesBuilder::default`.
+			voting_rules: vec![
+				VotingRule::BeforeBestBlockBy(2),
+				VotingRule::ThreeQuartersOfTheUnfinalizedChain,
+			],
+		}
+	}
+}
+
+impl GrandpaSettings {
+	/// The settings of `chain_spec`, or the defaults if it has none.
+	pub fn from_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+		crate::chain_spec::extension(chain_spec)
+	}
+
+	/// Override the settings with the command line options that are set.
+	pub fn with_overrides(mut self, params: &GrandpaParams) -> Self {
+		if let Some(gossip_duration) = params.gossip_duration {
+			self.gossip_duration = gossip_duration;
+		}
+		if let Some(justification_period) = params.justification_period {
+			self.justification_period = justification_period;
+		}
+		if let Some(observer_enabled) = params.observer_enabled {
+			self.observer_enabled = observer_enabled;
+		}
+		if !params.voting_rules.is_empty() {
+			self.voting_rules = params.voting_rules.clone();
+		}
+		self
+	}
+
+	/// The interval at which votes are gossiped.
+	pub fn gossip_duration(&self) -> Duration {
+		Duration::from_millis(self.gossip_duration.get())
+	}
+
+	/// The voting rule of the voter, combining [`Self::voting_rules`].
+	pub fn voting_rule<C>(&self) -> impl sc_consensus_grandpa::VotingRule<Block, C> + Clone
+	where
+		C: HeaderBackend<Block> + 'static,
+	{
+		self.voting_rules
+			.iter()
+			.fold(VotingRulesBuilder::new(), |builder, rule| match *rule {
+				VotingRule::BeforeBestBlockBy(blocks) => builder.add(BeforeBestBlockBy(blocks)),
+				VotingRule::ThreeQuartersOfTheUnfinalizedChain =>
+					builder.add(ThreeQuartersOfTheUnfinalizedChain),
+			})
+			.build()
+	}
+}
+
+/// Command line overrides of the GRANDPA settings of the chain spec.
+#[derive(Debug, Clone, clap::Args)]
+pub struct GrandpaParams {
+	/// The interval at which GRANDPA votes are gossiped, in milliseconds.
+	#[arg(long = "grandpa-gossip-duration", value_name = "MS")]
+	pub gossip_duration: Option<NonZeroU64>,
+
+	/// The minimum period of blocks on which GRANDPA justifications are imported and generated.
+	#[arg(long = "grandpa-justification-period", value_name = "BLOCKS")]
+	pub justification_period: Option<NonZeroU32>,
+
+	/// Whether non-authorities of the network run the GRANDPA observer protocol.
+	#[arg(long = "grandpa-observer-enabled", value_name = "BOOL")]
+	pub observer_enabled: Option<bool>,
+
+	/// A rule restricting the blocks GRANDPA votes for, replacing those of the chain spec. Can be
+	/// repeated.
+	///
+	/// Accepted values are `best-minus=<N>`, to vote no further than the best block minus `N`
+	/// blocks, and `three-quarters`, to vote no further than three quarters of the unfinalized
+	/// chain.
+	#[arg(long = "grandpa-voting-rule", value_name = "RULE")]
+	pub voting_rules: Vec<VotingRule>,
+}
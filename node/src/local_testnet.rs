@@ -0,0 +1,219 @@
+//! The `local-testnet` subcommand: run a multi-node network on this machine.
+//!
+//! Every node is a child process of this binary with its own base path, ports and node key.
+//! Their Aura and GRANDPA keys are derived from the well-known dev seeds (`//Alice`, `//Bob`, ...)
+//! and inserted into their keystores before they start, and every node is given the others as
+//! bootnodes. The output of all nodes is streamed with a per-node prefix until Ctrl-C.
+
+use crate::chain_spec::authority_keys_from_seed;
+use sc_cli::SubstrateCli;
+use sc_keystore::LocalKeystore;
+use sc_network::config::{ed25519, NodeKeyConfig, Secret};
+use sc_service::BasePath;
+use sp_core::{
+	crypto::{key_types, ByteArray},
+	hexdisplay::HexDisplay,
+};
+use sp_keystore::Keystore;
+use std::{path::PathBuf, process::Stdio};
+use tokio::{
+	io::{AsyncBufReadExt, AsyncRead, BufReader},
+	process::{Child, Command},
+};
+
+/// Seeds of the nodes started by [`LocalTestnetCmd`], in order.
+const DEV_SEEDS: [&str; 6] = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Run a local testnet of several validator nodes.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct LocalTestnetCmd {
+	/// Chain spec id or path shared by all nodes.
+	#[arg(long, value_name = "CHAIN_SPEC", default_value = "local")]
+	pub chain: String,
+
+	/// Number of nodes to start.
+	///
+	/// Nodes are named and keyed after the dev seeds `Alice`, `Bob`, `Charlie`, `Dave`, `Eve`
+	/// and `Ferdie`, then `Node7`, `Node8`, ... Only nodes whose keys are authorities in the
+	/// chain spec author blocks.
+	#[arg(long, short = 'n', default_value_t = 2, value_parser = clap::value_parser!(u16).range(1..))]
+	pub nodes: u16,
+
+	/// Directory holding the base path of every node, one `<name>` subdirectory per node.
+	///
+	/// A temporary directory, removed on exit, is used if not given.
+	#[arg(long, short = 'd', value_name = "PATH")]
+	pub base_path: Option<PathBuf>,
+
+	/// The libp2p port of the first node. Node `i` listens on `p2p-port + i`.
+	#[arg(long, value_name = "PORT", default_value_t = 30333)]
+	pub p2p_port: u16,
+
+	/// The RPC port of the first node. Node `i` listens on `rpc-port + i`.
+	#[arg(long, value_name = "PORT", default_value_t = 9944)]
+	pub rpc_port: u16,
+
+	/// The Prometheus port of the first node. Node `i` listens on `prometheus-port + i`.
+	#[arg(long, value_name = "PORT", default_value_t = 9615)]
+	pub prometheus_port: u16,
+
+	/// Extra arguments passed to every node, e.g. `-- --log runtime=debug`.
+	#[arg(last = true)]
+	pub node_args: Vec<String>,
+}
+
+/// Everything needed to spawn one node of the testnet.
+struct NodeSpec {
+	name: String,
+	base_path: PathBuf,
+	node_key: [u8; 32],
+	p2p_port: u16,
+	rpc_port: u16,
+	prometheus_port: u16,
+	multiaddr: String,
+}
+
+impl LocalTestnetCmd {
+	/// Run the command until Ctrl-C is pressed or one of the nodes exits.
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> sc_cli::Result<()> {
+		let chain_spec = cli.load_spec(&self.chain)?;
+		let base_path = match &self.base_path {
+			Some(path) => BasePath::new(path.clone()),
+			None => BasePath::new_temp_dir()?,
+		};
+
+		let nodes = (0..self.nodes)
+			.map(|index| self.node_spec(index, &base_path))
+			.collect::<sc_cli::Result<Vec<_>>>()?;
+		for node in &nodes {
+			insert_dev_keys(node, chain_spec.id())?;
+		}
+
+		let runtime = sc_cli::build_runtime()?;
+		runtime.block_on(self.run_nodes(&nodes))
+	}
+
+	fn node_spec(&self, index: u16, base_path: &BasePath) -> sc_cli::Result<NodeSpec> {
+		let name = DEV_SEEDS
+			.get(index as usize)
+			.map(|seed| seed.to_string())
+			.unwrap_or_else(|| format!("Node{}", index + 1));
+		let port = |base: u16| {
+			base.checked_add(index)
+				.ok_or_else(|| sc_cli::Error::Input(format!("no port left for node {name}")))
+		};
+		let (p2p_port, rpc_port, prometheus_port) =
+			(port(self.p2p_port)?, port(self.rpc_port)?, port(self.prometheus_port)?);
+
+		// Derive the node key from the name so that peer ids are stable across runs.
+		let node_key = sp_core::blake2_256(format!("//{name}//node-key").as_bytes());
+		let secret = ed25519::SecretKey::try_from_bytes(&mut node_key.clone())
+			.map_err(|e| sc_cli::Error::Input(format!("invalid node key for {name}: {e}")))?;
+		let peer_id = NodeKeyConfig::Ed25519(Secret::Input(secret))
+			.into_keypair()?
+			.public()
+			.to_peer_id();
+
+		Ok(NodeSpec {
+			base_path: base_path.path().join(&name),
+			multiaddr: format!("/ip4/127.0.0.1/tcp/{p2p_port}/p2p/{peer_id}"),
+			name,
+			node_key,
+			p2p_port,
+			rpc_port,
+			prometheus_port,
+		})
+	}
+
+	async fn run_nodes(&self, nodes: &[NodeSpec]) -> sc_cli::Result<()> {
+		let mut children = Vec::with_capacity(nodes.len());
+		for node in nodes {
+			let bootnodes = nodes.iter().filter(|other| other.name != node.name);
+			children.push((node.name.clone(), self.spawn_node(node, bootnodes)?));
+		}
+
+		let exited = {
+			let waits = children.iter_mut().map(|(_, child)| Box::pin(child.wait()));
+			tokio::select! {
+				res = tokio::signal::ctrl_c() => {
+					res?;
+					None
+				},
+				(status, index, _) = futures::future::select_all(waits) => Some((index, status)),
+			}
+		};
+
+		for (_, child) in &mut children {
+			let _ = child.kill().await;
+		}
+
+		match exited {
+			Some((index, status)) => Err(sc_cli::Error::Application(
+				format!("node {} exited with {}", children[index].0, status?).into(),
+			)),
+			None => Ok(()),
+		}
+	}
+
+	fn spawn_node<'a>(
+		&self,
+		node: &NodeSpec,
+		bootnodes: impl Iterator<Item = &'a NodeSpec>,
+	) -> sc_cli::Result<Child> {
+		let mut command = Command::new(std::env::current_exe()?);
+		command
+			.arg("--chain")
+			.arg(&self.chain)
+			.arg("--base-path")
+			.arg(&node.base_path)
+			.args(["--name", &node.name, "--validator"])
+			.args(["--node-key", &HexDisplay::from(&node.node_key).to_string()])
+			.args(["--port", &node.p2p_port.to_string()])
+			.args(["--rpc-port", &node.rpc_port.to_string()])
+			.args(["--prometheus-port", &node.prometheus_port.to_string()]);
+		for bootnode in bootnodes {
+			command.args(["--bootnodes", &bootnode.multiaddr]);
+		}
+		let mut child = command
+			.args(&self.node_args)
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.kill_on_drop(true)
+			.spawn()?;
+
+		println!("{:>8} | {} (rpc port {})", node.name, node.multiaddr, node.rpc_port);
+		if let Some(stdout) = child.stdout.take() {
+			tokio::spawn(stream_lines(node.name.clone(), stdout));
+		}
+		if let Some(stderr) = child.stderr.take() {
+			tokio::spawn(stream_lines(node.name.clone(), stderr));
+		}
+		Ok(child)
+	}
+}
+
+/// Insert the Aura and GRANDPA keys derived from the node's name into its keystore.
+fn insert_dev_keys(node: &NodeSpec, chain_id: &str) -> sc_cli::Result<()> {
+	let keystore_path = BasePath::new(node.base_path.clone()).config_dir(chain_id).join("keystore");
+	let keystore = LocalKeystore::open(keystore_path, None)?;
+	let (_, aura, grandpa) = authority_keys_from_seed(&node.name);
+	let suri = format!("//{}", node.name);
+
+	for (key_type, public) in
+		[(key_types::AURA, aura.as_slice()), (key_types::GRANDPA, grandpa.as_slice())]
+	{
+		keystore.insert(key_type, &suri, public).map_err(|_| {
+			sc_cli::Error::Input(format!("failed to insert {key_type:?} key of {}", node.name))
+		})?;
+	}
+	Ok(())
+}
+
+/// Print every line read from `output`, prefixed with the node's name.
+async fn stream_lines(name: String, output: impl AsyncRead + Unpin) {
+	let mut lines = BufReader::new(output).lines();
+	while let Ok(Some(line)) = lines.next_line().await {
+		println!("{name:>8} | {line}");
+	}
+}
@@ -1,12 +1,25 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
+mod backoff;
 mod benchmarking;
+mod block_rules;
 mod chain_spec;
 mod cli;
 mod command;
+mod extensions;
+mod generate_spec;
+mod grandpa;
+mod local_testnet;
+#[cfg(feature = "try-runtime")]
+mod rehearse_upgrade;
 mod rpc;
+mod schedule_upgrade;
 mod service;
+mod snapshot;
+#[cfg(feature = "try-runtime")]
+mod try_runtime;
+mod vesting_info;
 
 fn main() -> sc_cli::Result<()> {
 	command::run()
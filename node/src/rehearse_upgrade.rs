@@ -0,0 +1,242 @@
+//! The `rehearse-upgrade` subcommand: replay a snapshot written by `export-snapshot` with a
+//! candidate runtime.
+//!
+//! The candidate runtime replaces the code of the snapshot, as `System::set_code` would, then:
+//!
+//! 1. its migrations run through `TryRuntime_on_runtime_upgrade`, with the selected checks;
+//! 2. each block of the snapshot is executed on top of the result through
+//!    `TryRuntime_execute_block`.
+//!
+//! The state root of the blocks is not checked, as it diverges from the chain as soon as the
+//! candidate changes anything. The candidate must be built with the `try-runtime` feature.
+//!
+//! Everything runs in memory and the weight and storage changes of each step are reported as
+//! JSON. A key only counts as changed if its value differs from the one before the step, so writes
+//! of the value already stored are not reported. Nothing is written back to the snapshot.
+
+use crate::{
+	schedule_upgrade::check_wasm,
+	snapshot::{pallet_names, Snapshot},
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{TryStateSelect, UpgradeCheckSelect},
+	weights::Weight,
+};
+use sc_executor::{HeapAllocStrategy, WasmExecutor};
+use sp_core::{
+	bytes::to_hex,
+	storage::{well_known_keys, StateVersion},
+	traits::{CallContext, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::traits::{BlakeTwo256, Block as _, Header as _};
+use sp_state_machine::{
+	Backend as _, InMemoryBackend, OverlayedChanges, StateMachine, StorageCollection,
+};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Replay a state snapshot with a candidate runtime.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct RehearseUpgradeCmd {
+	/// Path to the snapshot, as written by `export-snapshot`.
+	#[arg(long, value_name = "PATH")]
+	pub snapshot: PathBuf,
+
+	/// Path to the Wasm blob of the candidate runtime, compressed or not.
+	#[arg(long, value_name = "PATH")]
+	pub wasm: PathBuf,
+
+	/// The checks to run around the migrations: `none`, `pre-and-post`, `try-state` or `all`.
+	#[arg(long, default_value = "pre-and-post")]
+	pub checks: UpgradeCheckSelect,
+
+	/// Also report each changed key with its values before and after the step, in hex.
+	#[arg(long)]
+	pub diff: bool,
+}
+
+impl RehearseUpgradeCmd {
+	/// Print the report as JSON, failing if a step fails or if the migrations do not fit in a
+	/// block.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let snapshot = Snapshot::load(&self.snapshot)?;
+		let code = std::fs::read(&self.wasm)?;
+		check_wasm(&code)?;
+		if !snapshot.pallets.is_empty() {
+			eprintln!(
+				"The snapshot only has the storage of {}, the migrations of other pallets see an \
+				 empty storage",
+				snapshot.pallets.join(", ")
+			);
+		}
+
+		let mut rehearsal = Rehearsal::new(&snapshot, code, self.diff);
+		let (weight, changes) = rehearsal.call("TryRuntime_on_runtime_upgrade", self.checks)?;
+		let (weight, max_weight) = <(Weight, Weight)>::decode(&mut &weight[..])
+			.map_err(|e| format!("invalid result of the runtime upgrade: {e}"))?;
+		let upgrade = serde_json::json!({
+			"weight": weight_json(weight),
+			"changes": changes,
+		});
+
+		let mut blocks = Vec::new();
+		for block in &snapshot.blocks {
+			let number = *block.header().number();
+			let args = (block, false, true, TryStateSelect::None);
+			let (block_weight, changes) = rehearsal
+				.call("TryRuntime_execute_block", args)
+				.map_err(|e| format!("block #{number}: {e}"))?;
+			let block_weight = Weight::decode(&mut &block_weight[..])
+				.map_err(|e| format!("invalid result of block #{number}: {e}"))?;
+			blocks.push(serde_json::json!({
+				"number": number,
+				"hash": format!("{:?}", block.hash()),
+				"weight": weight_json(block_weight),
+				"changes": changes,
+			}));
+		}
+
+		let report = serde_json::json!({
+			"number": snapshot.header.number(),
+			"hash": format!("{:?}", snapshot.header.hash()),
+			"upgrade": upgrade,
+			"blocks": blocks,
+		});
+		println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+
+		if weight.any_gt(max_weight) {
+			return Err("the migrations do not fit in a block, they must be split or made \
+				multi-block"
+				.into())
+		}
+		Ok(())
+	}
+}
+
+/// The keys whose value a step changed, by pallet.
+#[derive(Default, serde::Serialize)]
+struct Changes {
+	/// The number of keys set to a new value.
+	written: u32,
+	/// The number of keys removed.
+	removed: u32,
+	/// The changed keys, with `--diff`.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	keys: Vec<KeyChange>,
+}
+
+/// A key changed by a step, with its values before and after it, in hex.
+#[derive(serde::Serialize)]
+struct KeyChange {
+	key: String,
+	old: Option<String>,
+	new: Option<String>,
+}
+
+/// The state of the chain being replayed.
+struct Rehearsal {
+	backend: InMemoryBackend<BlakeTwo256>,
+	state_version: StateVersion,
+	code: Vec<u8>,
+	executor: WasmExecutor<sp_io::SubstrateHostFunctions>,
+	/// Whether to report the changed keys, not only their number.
+	diff: bool,
+}
+
+impl Rehearsal {
+	/// Load the state of `snapshot`, with `code` as the runtime.
+	fn new(snapshot: &Snapshot, code: Vec<u8>, diff: bool) -> Self {
+		let state_version = snapshot.state_version;
+		let mut backend = InMemoryBackend::from((snapshot.storage(), state_version));
+		backend.insert(
+			[(None, vec![(well_known_keys::CODE.to_vec(), Some(code.clone()))])],
+			state_version,
+		);
+
+		// Migrations may take way more memory than blocks are allowed to.
+		let heap = HeapAllocStrategy::Dynamic { maximum_pages: None };
+		let executor = WasmExecutor::builder()
+			.with_onchain_heap_alloc_strategy(heap)
+			.with_offchain_heap_alloc_strategy(heap)
+			.build();
+
+		Self { backend, state_version, code, executor, diff }
+	}
+
+	/// Call `method` of the runtime and commit its changes, returning its result and the changes
+	/// by pallet.
+	fn call(
+		&mut self,
+		method: &str,
+		args: impl Encode,
+	) -> sc_cli::Result<(Vec<u8>, BTreeMap<String, Changes>)> {
+		let code_fetcher = WrappedRuntimeCode(self.code.as_slice().into());
+		let runtime_code = RuntimeCode {
+			code_fetcher: &code_fetcher,
+			heap_pages: None,
+			hash: sp_core::blake2_256(&self.code).to_vec(),
+		};
+
+		let mut overlay = OverlayedChanges::default();
+		let result = StateMachine::new(
+			&self.backend,
+			&mut overlay,
+			&self.executor,
+			method,
+			&args.encode(),
+			&mut Default::default(),
+			&runtime_code,
+			CallContext::Offchain,
+		)
+		.execute()
+		.map_err(|e| format!("{method} failed: {e}"))?;
+
+		let changes = overlay
+			.drain_storage_changes(&self.backend, self.state_version)
+			.map_err(|e| format!("{method} changes cannot be committed: {e}"))?;
+		let by_pallet = self.changes_by_pallet(&changes.main_storage_changes)?;
+		self.backend
+			.apply_transaction(changes.transaction_storage_root, changes.transaction);
+		Ok((result, by_pallet))
+	}
+
+	/// The keys of `changes` whose value differs from the current state, by pallet.
+	fn changes_by_pallet(
+		&self,
+		changes: &StorageCollection,
+	) -> sc_cli::Result<BTreeMap<String, Changes>> {
+		let names = pallet_names();
+		let mut by_pallet = BTreeMap::<String, Changes>::new();
+		for (key, value) in changes {
+			let old = self
+				.backend
+				.storage(key)
+				.map_err(|e| format!("cannot read the state before the changes: {e}"))?;
+			if old == *value {
+				continue
+			}
+			let pallet = key
+				.get(..16)
+				.and_then(|prefix| names.get(prefix))
+				.map_or_else(|| "Unknown".to_string(), |name| name.to_string());
+			let entry = by_pallet.entry(pallet).or_default();
+			match value {
+				Some(_) => entry.written += 1,
+				None => entry.removed += 1,
+			}
+			if self.diff {
+				entry.keys.push(KeyChange {
+					key: to_hex(key, false),
+					old: old.map(|old| to_hex(&old, false)),
+					new: value.as_deref().map(|new| to_hex(new, false)),
+				});
+			}
+		}
+		Ok(by_pallet)
+	}
+}
+
+/// `weight` in the JSON report.
+fn weight_json(weight: Weight) -> serde_json::Value {
+	serde_json::json!({ "refTime": weight.ref_time(), "proofSize": weight.proof_size() })
+}
@@ -0,0 +1,84 @@
+//! RPC methods summarising the vesting of accounts.
+
+use std::sync::Arc;
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, types::error::ErrorObject};
+use node_template_runtime::{opaque::Block, AccountId, Balance, Hash};
+use node_template_runtime_api::{VestingApi as VestingRuntimeApi, VestingBalances};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+
+/// The vesting balances of an account, as returned by `vesting_accountInfo`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingInfo {
+	/// The part of the vesting schedules not vested yet.
+	pub locked: NumberOrHex,
+	/// The part of the vesting schedules vested so far, claimed or not.
+	pub vested: NumberOrHex,
+	/// The vested part still locked, released by calling `Vesting::vest`.
+	pub claimable: NumberOrHex,
+}
+
+impl From<VestingBalances<Balance>> for VestingInfo {
+	fn from(balances: VestingBalances<Balance>) -> Self {
+		Self {
+			locked: balances.locked.into(),
+			vested: balances.vested.into(),
+			claimable: balances.claimable.into(),
+		}
+	}
+}
+
+/// Vesting RPC methods.
+#[rpc(server)]
+pub trait VestingApi<BlockHash> {
+	/// Returns the vesting balances of `account` at the given block, or at the best block if `at`
+	/// is omitted. Returns `null` if the account has no vesting schedule.
+	#[method(name = "vesting_accountInfo")]
+	fn account_info(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<VestingInfo>>;
+}
+
+/// Provides RPC methods to query vesting balances.
+pub struct Vesting<C> {
+	client: Arc<C>,
+}
+
+impl<C> Vesting<C> {
+	/// Creates a new instance of the Vesting RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+impl<C> VestingApiServer<Hash> for Vesting<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: VestingRuntimeApi<Block, AccountId, Balance>,
+{
+	fn account_info(&self, account: AccountId, at: Option<Hash>) -> RpcResult<Option<VestingInfo>> {
+		account_info(&*self.client, account, at).map_err(|e| {
+			ErrorObject::owned(1, "Unable to query the vesting balances.", Some(e.to_string()))
+		})
+	}
+}
+
+/// The vesting balances of `account` at block `at`, or at the best block.
+pub fn account_info<C>(
+	client: &C,
+	account: AccountId,
+	at: Option<Hash>,
+) -> Result<Option<VestingInfo>, sp_api::ApiError>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: VestingRuntimeApi<Block, AccountId, Balance>,
+{
+	let at_hash = at.unwrap_or_else(|| client.info().best_hash);
+	Ok(client.runtime_api().vesting_balances(at_hash, account)?.map(Into::into))
+}
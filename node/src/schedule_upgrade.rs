@@ -0,0 +1,112 @@
+//! The `schedule-upgrade` subcommand: build the calls enacting a runtime upgrade at a given block.
+//!
+//! The upgrade is done in two steps, so that the large `System::set_code` call is uploaded once
+//! and governance only votes on its hash:
+//!
+//! 1. `Preimage::note_preimage` uploads the encoded `set_code` call. Any account can submit it,
+//!    against a deposit returned by `Preimage::unnote_preimage`. As a normal transaction, it must
+//!    fit in the normal-class block length, which limits the size of the new runtime.
+//! 2. `Scheduler::schedule` enacts `Governance::dispatch_preimage_as_root` with the hash of the
+//!    preimage at the given block. It needs the root or governance origin, so it is either wrapped
+//!    in `Sudo::sudo` or proposed as is to the council or the technical committee.
+//!
+//! The calls are printed as hex-encoded call data, to be signed and submitted with any wallet,
+//! e.g. through the "Decode" tab of Polkadot-JS Apps. They are encoded for the runtime this node
+//! was built with, which must match the runtime on chain.
+
+use frame_support::dispatch::{DispatchClass, GetDispatchInfo};
+use node_template_runtime::{BlockLength, BlockNumber, RuntimeCall, SystemCall};
+use sp_core::{bytes::to_hex, Encode, H256};
+use std::path::PathBuf;
+
+/// How the scheduling call gets the root origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Via {
+	/// Wrap the scheduling call in `Sudo::sudo`.
+	Sudo,
+	/// Leave the scheduling call as is, to be proposed as a council or technical committee motion.
+	Governance,
+}
+
+/// Build the calls scheduling a runtime upgrade.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ScheduleUpgradeCmd {
+	/// Path to the Wasm blob of the new runtime, compressed or not.
+	#[arg(long, value_name = "PATH")]
+	pub wasm: PathBuf,
+
+	/// Block number at which the upgrade is enacted.
+	#[arg(long, value_name = "BLOCK")]
+	pub at: BlockNumber,
+
+	/// How the scheduling call gets the root origin.
+	#[arg(long, value_enum, default_value_t = Via::Sudo)]
+	pub via: Via,
+}
+
+impl ScheduleUpgradeCmd {
+	/// Print the calls as JSON.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let code = std::fs::read(&self.wasm)?;
+		check_wasm(&code)?;
+
+		let set_code = RuntimeCall::System(SystemCall::set_code { code });
+		let preimage = set_code.encode();
+		if preimage.len() > pallet_preimage::MAX_SIZE as usize {
+			return Err(sc_cli::Error::Input(format!(
+				"the set_code call is {} bytes long, preimages are limited to {} bytes",
+				preimage.len(),
+				pallet_preimage::MAX_SIZE,
+			)))
+		}
+		let hash = H256(sp_core::blake2_256(&preimage));
+
+		let note_preimage =
+			RuntimeCall::Preimage(pallet_preimage::Call::note_preimage { bytes: preimage.clone() });
+		let max_length = *BlockLength::get().max.get(DispatchClass::Normal) as usize;
+		if note_preimage.encoded_size() > max_length {
+			return Err(sc_cli::Error::Input(format!(
+				"the note_preimage call is {} bytes long, transactions are limited to {} bytes",
+				note_preimage.encoded_size(),
+				max_length,
+			)))
+		}
+		let enact = RuntimeCall::Governance(pallet_governance::Call::dispatch_preimage_as_root {
+			hash,
+			len: preimage.len() as u32,
+			call_weight_witness: set_code.get_dispatch_info().weight,
+		});
+		let schedule = RuntimeCall::Scheduler(pallet_scheduler::Call::schedule {
+			when: self.at,
+			maybe_periodic: None,
+			priority: 0,
+			call: Box::new(enact),
+		});
+		let schedule = match self.via {
+			Via::Sudo => RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(schedule) }),
+			Via::Governance => schedule,
+		};
+
+		let output = serde_json::json!({
+			"preimageHash": format!("{hash:?}"),
+			"preimageLength": preimage.len(),
+			"notePreimage": to_hex(&note_preimage.encode(), false),
+			"schedule": to_hex(&schedule.encode(), false),
+			// The `length_bound` to give to `propose` and `close` when proposing `schedule`.
+			"scheduleLength": schedule.encoded_size(),
+		});
+		println!("{}", serde_json::to_string_pretty(&output).map_err(|e| e.to_string())?);
+		Ok(())
+	}
+}
+
+/// Reject files that are neither a Wasm module nor a compressed one.
+pub(crate) fn check_wasm(code: &[u8]) -> sc_cli::Result<()> {
+	let code =
+		sp_maybe_compressed_blob::decompress(code, sp_maybe_compressed_blob::CODE_BLOB_BOMB_LIMIT)
+			.map_err(|e| sc_cli::Error::Input(format!("invalid compressed runtime: {e}")))?;
+	if !code.starts_with(b"\0asm") {
+		return Err(sc_cli::Error::Input("the file is not a Wasm blob".into()))
+	}
+	Ok(())
+}
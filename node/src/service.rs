@@ -1,15 +1,36 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use futures::FutureExt;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use crate::{
+	backoff::BackoffParams,
+	block_rules::{BlockRules, BlockRulesImport, BlockRulesParams},
+	chain_spec::{self, RoleBootNodes},
+	cli::{Cli, Sealing},
+	extensions::NodeExtensions,
+	grandpa::{GrandpaParams, GrandpaSettings},
+};
+use futures::{
+	channel::mpsc,
+	stream::{self, BoxStream},
+	FutureExt, StreamExt,
+};
+use node_template_runtime::{self, opaque::Block, Hash, RuntimeApi};
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 use sc_consensus_grandpa::SharedVoterState;
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, EngineCommand, ManualSealParams,
+};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager, WarpSyncParams};
-use sc_telemetry::{Telemetry, TelemetryWorker};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker};
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use std::{
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
 
 pub(crate) type FullClient = sc_service::TFullClient<
 	Block,
@@ -18,10 +39,7 @@ pub(crate) type FullClient = sc_service::TFullClient<
 >;
 type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
-
-/// The minimum period of blocks on which justifications will be
-/// imported and generated.
-const GRANDPA_JUSTIFICATION_PERIOD: u32 = 512;
+type FullPool = sc_transaction_pool::FullPool<Block, FullClient>;
 
 pub type Service = sc_service::PartialComponents<
 	FullClient,
@@ -37,6 +55,20 @@ pub type Service = sc_service::PartialComponents<
 >;
 
 pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
+	new_partial_with(
+		config,
+		&GrandpaSettings::from_chain_spec(&*config.chain_spec),
+		BlockRules::from_chain_spec(&*config.chain_spec),
+	)
+}
+
+/// [`new_partial`], with the given GRANDPA settings and block rules instead of those of the chain
+/// spec.
+fn new_partial_with(
+	config: &Configuration,
+	grandpa: &GrandpaSettings,
+	block_rules: BlockRules,
+) -> Result<Service, ServiceError> {
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -74,7 +106,7 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 
 	let (grandpa_block_import, grandpa_link) = sc_consensus_grandpa::block_import(
 		client.clone(),
-		GRANDPA_JUSTIFICATION_PERIOD,
+		grandpa.justification_period.get(),
 		&client,
 		select_chain.clone(),
 		telemetry.as_ref().map(|x| x.handle()),
@@ -83,7 +115,7 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 	let cidp_client = client.clone();
 	let import_queue =
 		sc_consensus_aura::import_queue::<AuraPair, _, _, _, _, _>(ImportQueueParams {
-			block_import: grandpa_block_import.clone(),
+			block_import: BlockRulesImport::new(grandpa_block_import.clone(), block_rules),
 			justification_import: Some(Box::new(grandpa_block_import.clone())),
 			client: client.clone(),
 			create_inherent_data_providers: move |parent_hash, _| {
@@ -123,8 +155,55 @@ pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
 	})
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+/// The settings of the node on top of the Substrate [`Configuration`].
+pub struct NodeOptions {
+	/// When set, Aura and the GRANDPA voter are replaced by a manual-seal authorship task that
+	/// finalizes its own blocks.
+	pub sealing: Option<Sealing>,
+	/// The offchain storage values fed to the offchain workers.
+	pub extensions: NodeExtensions,
+	/// Overrides of the GRANDPA settings of the chain spec.
+	pub grandpa: GrandpaParams,
+	/// How Aura backs off authoring while finality lags.
+	pub backoff: BackoffParams,
+	/// Bad and fork blocks, on top of the ones of the chain spec.
+	pub block_rules: BlockRulesParams,
+}
+
+impl NodeOptions {
+	/// The settings given on the command line.
+	pub fn from_cli(cli: &Cli) -> Self {
+		Self {
+			sealing: cli.sealing,
+			extensions: NodeExtensions::from_cli(cli),
+			grandpa: cli.grandpa.clone(),
+			backoff: cli.backoff.clone(),
+			block_rules: cli.block_rules.clone(),
+		}
+	}
+}
+
+/// Builds a new service for a full client, with the settings of `options`.
+///
+/// The boot nodes the chain spec recommends for the role of the node are added to the network
+/// configuration. Its bad and fork blocks are rejected by the import queue, and removed from the
+/// database if they are not finalized.
+pub fn new_full(
+	mut config: Configuration,
+	options: NodeOptions,
+) -> Result<TaskManager, ServiceError> {
+	let NodeOptions {
+		sealing,
+		extensions,
+		grandpa: grandpa_params,
+		backoff: backoff_params,
+		block_rules: block_rules_params,
+	} = options;
+	chain_spec::extension::<RoleBootNodes>(&*config.chain_spec).apply(&mut config);
+	let grandpa_settings =
+		GrandpaSettings::from_chain_spec(&*config.chain_spec).with_overrides(&grandpa_params);
+	let block_rules =
+		BlockRules::from_chain_spec(&*config.chain_spec).with_overrides(&block_rules_params);
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -134,7 +213,8 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		select_chain,
 		transaction_pool,
 		other: (block_import, grandpa_link, mut telemetry),
-	} = new_partial(&config)?;
+	} = new_partial_with(&config, &grandpa_settings, block_rules.clone())?;
+	block_rules.purge(client.clone(), &*backend)?;
 
 	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
 
@@ -184,22 +264,38 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			.run(client.clone(), task_manager.spawn_handle())
 			.boxed(),
 		);
+
+		if let Some(task) = backend
+			.offchain_storage()
+			.and_then(|storage| extensions.offchain_storage_task(&*client, storage))
+		{
+			task_manager
+				.spawn_handle()
+				.spawn("offchain-storage-feed", "offchain-worker", task);
+		}
 	}
 
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
-	let backoff_authoring_blocks: Option<()> = None;
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 
+	// Commands for the manual-seal task, fed by the `engine_*` RPC methods.
+	let manual_seal_channel = sealing.map(|_| mpsc::channel(1024));
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let command_sink = manual_seal_channel.as_ref().map(|(sink, _)| sink.clone());
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				deny_unsafe,
+				command_sink: command_sink.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -219,7 +315,24 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		telemetry: telemetry.as_mut(),
 	})?;
 
+	if let Some((sealing, (_, rpc_commands))) = sealing.zip(manual_seal_channel) {
+		start_manual_seal(
+			sealing,
+			rpc_commands,
+			client,
+			select_chain,
+			transaction_pool,
+			&task_manager,
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle()),
+		)?;
+
+		network_starter.start_network();
+		return Ok(task_manager)
+	}
+
 	if role.is_authority() {
+		let backoff_authoring_blocks = backoff_params.strategy(prometheus_registry.as_ref())?;
 		let proposer_factory = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
@@ -273,11 +386,10 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		let keystore = if role.is_authority() { Some(keystore_container.keystore()) } else { None };
 
 		let grandpa_config = sc_consensus_grandpa::Config {
-			// FIXME #1578 make this available through chainspec
-			gossip_duration: Duration::from_millis(333),
-			justification_generation_period: GRANDPA_JUSTIFICATION_PERIOD,
+			gossip_duration: grandpa_settings.gossip_duration(),
+			justification_generation_period: grandpa_settings.justification_period.get(),
 			name: Some(name),
-			observer_enabled: false,
+			observer_enabled: grandpa_settings.observer_enabled,
 			keystore,
 			local_role: role,
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
@@ -296,7 +408,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 			network,
 			sync: Arc::new(sync_service),
 			notification_service: grandpa_notification_service,
-			voting_rule: sc_consensus_grandpa::VotingRulesBuilder::default().build(),
+			voting_rule: grandpa_settings.voting_rule(),
 			prometheus_registry,
 			shared_voter_state: SharedVoterState::empty(),
 			telemetry: telemetry.as_ref().map(|x| x.handle()),
@@ -315,3 +427,104 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 	network_starter.start_network();
 	Ok(task_manager)
 }
+
+/// Spawns the manual-seal authorship task that replaces Aura and GRANDPA in development.
+///
+/// Blocks requested through the `engine_createBlock` RPC are always honoured. Depending on
+/// `sealing`, blocks are additionally sealed whenever a transaction is imported into the pool or
+/// at a fixed interval; those blocks are finalized right away.
+fn start_manual_seal(
+	sealing: Sealing,
+	rpc_commands: mpsc::Receiver<EngineCommand<Hash>>,
+	client: Arc<FullClient>,
+	select_chain: FullSelectChain,
+	transaction_pool: Arc<FullPool>,
+	task_manager: &TaskManager,
+	prometheus_registry: Option<&prometheus_endpoint::Registry>,
+	telemetry: Option<TelemetryHandle>,
+) -> Result<(), ServiceError> {
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry,
+		telemetry,
+	);
+
+	let seal = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: true,
+		parent_hash: None,
+		sender: None,
+	};
+	let commands_stream: BoxStream<'static, EngineCommand<Hash>> = match sealing {
+		Sealing::Manual => rpc_commands.boxed(),
+		Sealing::Instant => stream::select(
+			rpc_commands,
+			transaction_pool.import_notification_stream().map(move |_| seal(false)),
+		)
+		.boxed(),
+		Sealing::Interval(millis) => stream::select(
+			rpc_commands,
+			stream::unfold((), move |()| async move {
+				futures_timer::Delay::new(Duration::from_millis(millis)).await;
+				Some((seal(true), ()))
+			}),
+		)
+		.boxed(),
+	};
+
+	let slot_duration = sc_consensus_aura::slot_duration(&*client)?;
+	let clock = Arc::new(SealingClock::new(slot_duration.as_millis()));
+
+	let authorship = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+		block_import: client.clone(),
+		env: proposer_factory,
+		client: client.clone(),
+		pool: transaction_pool,
+		commands_stream,
+		select_chain,
+		consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client))),
+		create_inherent_data_providers: move |_, ()| {
+			let clock = clock.clone();
+			async move { Ok(clock.next()) }
+		},
+	});
+
+	// the manual-seal task replaces block authoring, so if it fails we take down the service
+	// with it.
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		Some("block-authoring"),
+		authorship,
+	);
+
+	Ok(())
+}
+
+/// Timestamps handed to manually sealed blocks.
+///
+/// `pallet_aura` requires every block to be in a later slot than its parent, so each timestamp
+/// is at least one slot ahead of the previous one, even when several blocks are sealed within
+/// the same wall-clock slot.
+struct SealingClock {
+	slot_duration: u64,
+	last: AtomicU64,
+}
+
+impl SealingClock {
+	fn new(slot_duration: u64) -> Self {
+		Self { slot_duration, last: AtomicU64::new(0) }
+	}
+
+	fn next(&self) -> sp_timestamp::InherentDataProvider {
+		let now = sp_timestamp::Timestamp::current().as_millis();
+		let advance = |last: u64| now.max(last.saturating_add(self.slot_duration));
+		let last = self
+			.last
+			.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(advance(last)))
+			.expect("closure always returns `Some`; qed");
+
+		sp_timestamp::InherentDataProvider::new(advance(last).into())
+	}
+}
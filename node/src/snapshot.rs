@@ -0,0 +1,272 @@
+//! State snapshots: the storage of a block, and optionally the blocks following it, in a compact
+//! file reusable for testing.
+//!
+//! A snapshot file starts with [`MAGIC`] and the little-endian `u16` [`SNAPSHOT_VERSION`], followed
+//! by the zstd-compressed SCALE encoding of a [`Snapshot`]. Files of other versions are rejected
+//! instead of being misread.
+//!
+//! The `export-snapshot` subcommand writes snapshots from the local database, and the
+//! `rehearse-upgrade` subcommand replays them with a candidate runtime.
+
+use codec::{Decode, Encode};
+use frame_support::traits::PalletsInfoAccess;
+use node_template_runtime::{
+	opaque::{Block, Header},
+	AllPalletsWithSystem,
+};
+use sc_cli::{BlockNumberOrHash, CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{BlockBackend, StorageProvider};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	storage::{well_known_keys, ChildInfo, StateVersion, Storage, StorageChild, StorageKey},
+	twox_128,
+};
+use sp_runtime::traits::Header as _;
+use std::{
+	collections::BTreeMap,
+	path::{Path, PathBuf},
+};
+
+/// The first bytes of a snapshot file.
+pub const MAGIC: [u8; 4] = *b"snap";
+
+/// The version of the snapshot format written by this node.
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+/// The maximum size of a decompressed snapshot.
+const MAX_SNAPSHOT_SIZE: usize = 1 << 34;
+
+/// The state of a block, and optionally the blocks following it.
+#[derive(Encode, Decode)]
+pub struct Snapshot {
+	/// The state version of the runtime at the exported block.
+	pub state_version: StateVersion,
+	/// The header of the exported block.
+	pub header: Header,
+	/// The pallets the state was filtered by, or empty if the whole state was exported.
+	pub pallets: Vec<String>,
+	/// The key-value pairs of the main trie.
+	pub top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// The key-value pairs of the default child tries, by child trie key.
+	pub children: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+	/// The blocks following the exported block, oldest first.
+	pub blocks: Vec<Block>,
+}
+
+impl Snapshot {
+	/// Read a snapshot file.
+	pub fn load(path: &Path) -> sc_cli::Result<Self> {
+		let file = std::fs::read(path)?;
+		let invalid = |e: String| sc_cli::Error::Input(format!("{}: {e}", path.display()));
+
+		let payload = file
+			.strip_prefix(&MAGIC[..])
+			.ok_or_else(|| invalid("not a snapshot file".into()))?;
+		let version = payload
+			.get(..2)
+			.map(|version| u16::from_le_bytes([version[0], version[1]]))
+			.ok_or_else(|| invalid("truncated snapshot".into()))?;
+		if version != SNAPSHOT_VERSION {
+			return Err(invalid(format!(
+				"snapshot version {version}, this node reads version {SNAPSHOT_VERSION}"
+			)))
+		}
+
+		let encoded = sp_maybe_compressed_blob::decompress(&payload[2..], MAX_SNAPSHOT_SIZE)
+			.map_err(|e| invalid(e.to_string()))?;
+		Self::decode(&mut &encoded[..]).map_err(|e| invalid(e.to_string()))
+	}
+
+	/// Write the snapshot to a file, returning its size.
+	pub fn save(&self, path: &Path) -> sc_cli::Result<usize> {
+		let payload = sp_maybe_compressed_blob::compress(&self.encode(), MAX_SNAPSHOT_SIZE)
+			.ok_or_else(|| sc_cli::Error::Input("the snapshot is too large".into()))?;
+
+		let mut file = MAGIC.to_vec();
+		file.extend(SNAPSHOT_VERSION.to_le_bytes());
+		file.extend(payload);
+		std::fs::write(path, &file)?;
+		Ok(file.len())
+	}
+
+	/// The storage of the snapshot, to build externalities from.
+	pub fn storage(&self) -> Storage {
+		Storage {
+			top: self.top.iter().cloned().collect(),
+			children_default: self
+				.children
+				.iter()
+				.map(|(key, data)| {
+					let child = StorageChild {
+						data: data.iter().cloned().collect(),
+						child_info: ChildInfo::new_default(key),
+					};
+					(key.clone(), child)
+				})
+				.collect(),
+		}
+	}
+}
+
+/// The names of the pallets of the runtime, by storage prefix.
+pub fn pallet_names() -> BTreeMap<[u8; 16], &'static str> {
+	AllPalletsWithSystem::infos()
+		.into_iter()
+		.map(|info| (twox_128(info.name.as_bytes()), info.name))
+		.collect()
+}
+
+/// Export the state of a block, and optionally the blocks following it, to a snapshot file.
+///
+/// Unlike `export-state`, the snapshot only contains the raw storage, which `rehearse-upgrade`
+/// loads without a chain spec.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportSnapshotCmd {
+	/// The block to export the state of, by number or hash. Defaults to the best block.
+	#[arg(long, value_name = "BLOCK")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Only export the storage of this pallet. Can be repeated.
+	#[arg(long = "pallet", value_name = "NAME")]
+	pub pallets: Vec<String>,
+
+	/// Also export this many blocks following the exported one, to replay on top of its state.
+	///
+	/// Replaying blocks needs the whole state, so this cannot be combined with `--pallet`.
+	#[arg(long, value_name = "COUNT", default_value_t = 0, conflicts_with = "pallets")]
+	pub blocks: u32,
+
+	/// Path to the snapshot file to write.
+	#[arg(long, value_name = "PATH")]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportSnapshotCmd {
+	/// Write the snapshot.
+	pub fn run<C, BE>(&self, client: &C) -> sc_cli::Result<()>
+	where
+		C: StorageProvider<Block, BE>
+			+ HeaderBackend<Block>
+			+ BlockBackend<Block>
+			+ ProvideRuntimeApi<Block>,
+		C::Api: Core<Block>,
+		BE: sc_client_api::Backend<Block>,
+	{
+		let hash = match &self.at {
+			Some(at) => client.expect_block_hash_from_id(&at.parse::<Block>()?)?,
+			None => client.info().best_hash,
+		};
+		let header = client.expect_header(hash)?;
+		let state_version = client
+			.runtime_api()
+			.version(hash)
+			.map_err(|e| sc_cli::Error::Application(Box::new(e)))?
+			.state_version();
+
+		let pairs = |prefix: Option<&StorageKey>| -> sc_cli::Result<Vec<_>> {
+			let pairs = client.storage_pairs(hash, prefix, None)?;
+			Ok(pairs.map(|(key, value)| (key.0, value.0)).collect())
+		};
+		let prefixes = self.prefixes()?;
+		let mut top = if prefixes.is_empty() { pairs(None)? } else { Vec::new() };
+		for prefix in &prefixes {
+			top.extend(pairs(Some(prefix))?);
+		}
+
+		// Child tries are only reachable through their root in the main trie, and belong to no
+		// pallet prefix.
+		let mut children = Vec::new();
+		let child_prefix = StorageKey(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec());
+		let child_keys = if prefixes.is_empty() {
+			client.storage_keys(hash, Some(&child_prefix), None)?.collect()
+		} else {
+			Vec::new()
+		};
+		for key in child_keys {
+			let child_key = key.0[child_prefix.0.len()..].to_vec();
+			let child_info = ChildInfo::new_default(&child_key);
+			let mut data = Vec::new();
+			for key in client.child_storage_keys(hash, child_info.clone(), None, None)? {
+				if let Some(value) = client.child_storage(hash, &child_info, &key)? {
+					data.push((key.0, value.0));
+				}
+			}
+			children.push((child_key, data));
+		}
+		top.retain(|(key, _)| !key.starts_with(well_known_keys::CHILD_STORAGE_KEY_PREFIX));
+
+		let mut blocks = Vec::new();
+		for number in (1..=self.blocks).map(|i| header.number() + i) {
+			let block = client
+				.hash(number)?
+				.and_then(|hash| client.block(hash).transpose())
+				.transpose()?
+				.ok_or_else(|| sc_cli::Error::Input(format!("block #{number} is not imported")))?;
+			blocks.push(block.block);
+		}
+
+		let snapshot = Snapshot {
+			state_version,
+			header,
+			pallets: self.pallets.clone(),
+			top,
+			children,
+			blocks,
+		};
+		let size = snapshot.save(&self.output)?;
+		println!(
+			"Exported {} keys at block #{} ({hash:?}) and {} following blocks to {} ({size} bytes)",
+			snapshot.top.len() +
+				snapshot.children.iter().map(|(_, data)| data.len()).sum::<usize>(),
+			snapshot.header.number(),
+			snapshot.blocks.len(),
+			self.output.display(),
+		);
+		Ok(())
+	}
+
+	/// The storage prefixes of the pallets to export, checking that they exist.
+	fn prefixes(&self) -> sc_cli::Result<Vec<StorageKey>> {
+		let names = pallet_names();
+		self.pallets
+			.iter()
+			.map(|pallet| {
+				let prefix = twox_128(pallet.as_bytes());
+				if !names.contains_key(&prefix) {
+					return Err(sc_cli::Error::Input(format!(
+						"unknown pallet `{pallet}`, the runtime has: {}",
+						names.values().copied().collect::<Vec<_>>().join(", ")
+					)))
+				}
+				Ok(StorageKey(prefix.to_vec()))
+			})
+			.collect()
+	}
+}
+
+impl CliConfiguration for ExportSnapshotCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,65 @@
+//! The `try-runtime` subcommand: rehearse the runtime upgrade to the runtime of this node against a
+//! snapshot of the chain state.
+//!
+//! The snapshot is a file created by `try-runtime create-snapshot` of the standalone
+//! [try-runtime CLI](https://github.com/paritytech/try-runtime-cli). The migrations of the runtime
+//! this node was built with, along with their `pre_upgrade` and `post_upgrade` checks, run natively
+//! on top of it. Nothing is written back to the snapshot.
+
+use frame_remote_externalities::{Builder, Mode, OfflineConfig, SnapshotConfig};
+use frame_support::{
+	traits::UpgradeCheckSelect,
+	weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, Weight},
+};
+use node_template_runtime::{opaque::Block, BlockWeights, Executive};
+use std::path::PathBuf;
+
+/// Run the migrations of this node's runtime against a state snapshot.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct TryRuntimeCmd {
+	/// Path to the state snapshot.
+	#[arg(long, value_name = "PATH")]
+	pub snapshot: PathBuf,
+
+	/// The checks to run around the migrations: `none`, `pre-and-post`, `try-state` or `all`.
+	#[arg(long, default_value = "pre-and-post")]
+	pub checks: UpgradeCheckSelect,
+}
+
+impl TryRuntimeCmd {
+	/// Run the migrations, failing if any of the checks fails or if they do not fit in a block.
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let mut ext = sc_cli::build_runtime()?.block_on(
+			Builder::<Block>::new()
+				.mode(Mode::Offline(OfflineConfig {
+					state_snapshot: SnapshotConfig::new(&self.snapshot),
+				}))
+				.build(),
+		)?;
+		println!("Loaded the state at block {:?}", ext.block_hash);
+
+		let weight = ext
+			.execute_with(|| Executive::try_runtime_upgrade(self.checks))
+			.map_err(|e| format!("the runtime upgrade failed: {e:?}"))?;
+		print_weight(weight)
+	}
+}
+
+/// Print the weight of the migrations, failing if it exceeds the maximum weight of a block.
+fn print_weight(weight: Weight) -> sc_cli::Result<()> {
+	let max = BlockWeights::get().max_block;
+	println!(
+		"The migrations consumed {}ms of execution time and {} bytes of proof size, {}% and {}% \
+		 of a block",
+		weight.ref_time() / WEIGHT_REF_TIME_PER_MILLIS,
+		weight.proof_size(),
+		weight.ref_time().saturating_mul(100) / max.ref_time().max(1),
+		weight.proof_size().saturating_mul(100) / max.proof_size().max(1),
+	);
+	if weight.any_gt(max) {
+		return Err("the migrations do not fit in a block, they must be split or made \
+			multi-block"
+			.into())
+	}
+	Ok(())
+}
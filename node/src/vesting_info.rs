@@ -0,0 +1,58 @@
+//! The `vesting-info` subcommand: show the vesting balances of an account from the local database.
+
+use crate::rpc::vesting::account_info;
+use node_template_runtime::{opaque::Block, AccountId, Balance};
+use node_template_runtime_api::VestingApi;
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+
+/// Show the locked, vested and claimable balances of an account at the best block.
+///
+/// The same information is served by running nodes through the `vesting_accountInfo` RPC method.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct VestingInfoCmd {
+	/// The account, in SS58 or hex format.
+	#[arg(value_name = "ACCOUNT")]
+	pub account: AccountId,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VestingInfoCmd {
+	/// Print the vesting balances of the account as JSON, or `null` if it has no vesting schedule.
+	pub fn run<C>(&self, client: &C) -> sc_cli::Result<()>
+	where
+		C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+		C::Api: VestingApi<Block, AccountId, Balance>,
+	{
+		let info = account_info(client, self.account.clone(), None)
+			.map_err(|e| sc_cli::Error::Application(Box::new(e)))?;
+		println!("{}", serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for VestingInfoCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,26 @@
+//! Benchmarking setup for pallet-fee-distribution
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as FeeDistribution;
+use frame_benchmarking::v2::*;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_author_share() -> Result<(), BenchmarkError> {
+		let origin =
+			T::UpdateOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let share = Perbill::from_percent(50);
+		#[extrinsic_call]
+		set_author_share(origin as T::RuntimeOrigin, share);
+
+		assert_eq!(AuthorShare::<T>::get(), share);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(FeeDistribution, crate::mock::new_test_ext(), crate::mock::Test);
+}
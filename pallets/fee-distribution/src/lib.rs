@@ -0,0 +1,239 @@
+//! # Fee Distribution Pallet
+//!
+//! A pallet that routes transaction fees and tips instead of burning them.
+//!
+//! ## Overview
+//!
+//! [`DealWithFees`] is meant to be used as the `OnUnbalanced` handler of
+//! `pallet_transaction_payment::CurrencyAdapter`. For every transaction it:
+//!
+//! - splits the fee between the author of the current block and the treasury account, giving the
+//!   author the [`AuthorShare`] of the fee and the treasury the rest;
+//! - gives the whole tip to the author;
+//! - emits [`Event::FeesDistributed`] describing the split.
+//!
+//! Fees and tips paid in an asset, e.g. by `pallet_asset_tx_payment`, are split the same way by
+//! [`Pallet::distribute_asset_fees`], which emits [`Event::AssetFeesDistributed`] instead.
+//!
+//! The block author is resolved from the pre-runtime digest of the block being built through
+//! [`Config::FindAuthor`]. If no author can be found, fees and tips go to the treasury.
+//!
+//! [`AuthorShare`] defaults to [`Config::DefaultAuthorShare`] and can be changed by
+//! [`Config::UpdateOrigin`] through [`Pallet::set_author_share`].
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use frame_support::traits::{fungibles, Currency, FindAuthor, Get, Imbalance, OnUnbalanced};
+use sp_runtime::{traits::Zero, Perbill};
+
+/// The balance type of [`Config::Currency`].
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The negative imbalance type of [`Config::Currency`], i.e. withdrawn funds waiting for a home.
+pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The currency fees are paid in.
+		type Currency: Currency<Self::AccountId>;
+		/// The identifier of the assets fees can also be paid in.
+		type AssetId: Parameter + MaxEncodedLen;
+		/// Finds the author of the current block from its pre-runtime digests.
+		type FindAuthor: FindAuthor<Self::AccountId>;
+		/// The account receiving the part of the fees not given to the block author.
+		#[pallet::constant]
+		type TreasuryAccount: Get<Self::AccountId>;
+		/// The share of fees given to the block author until [`AuthorShare`] is set.
+		#[pallet::constant]
+		type DefaultAuthorShare: Get<Perbill>;
+		/// The origin allowed to change [`AuthorShare`].
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::type_value]
+	pub fn DefaultForAuthorShare<T: Config>() -> Perbill {
+		T::DefaultAuthorShare::get()
+	}
+
+	/// The share of each fee given to the block author. The rest goes to the treasury.
+	#[pallet::storage]
+	pub type AuthorShare<T> = StorageValue<_, Perbill, ValueQuery, DefaultForAuthorShare<T>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The fee and tip of a transaction were distributed.
+		FeesDistributed {
+			/// The author of the block, if it could be found.
+			author: Option<T::AccountId>,
+			/// The part of the fee given to the author.
+			author_fee: BalanceOf<T>,
+			/// The part of the fee given to the treasury.
+			treasury_fee: BalanceOf<T>,
+			/// The tip, given to the author or to the treasury if there is no author.
+			tip: BalanceOf<T>,
+		},
+		/// The fee and tip of a transaction paid in an asset were distributed, as in
+		/// [`Event::FeesDistributed`].
+		AssetFeesDistributed {
+			/// The asset the fee and tip were paid in.
+			asset: T::AssetId,
+			/// The author of the block, if it could be found.
+			author: Option<T::AccountId>,
+			/// The part of the fee given to the author.
+			author_fee: BalanceOf<T>,
+			/// The part of the fee given to the treasury.
+			treasury_fee: BalanceOf<T>,
+			/// The tip, given to the author or to the treasury if there is no author.
+			tip: BalanceOf<T>,
+		},
+		/// The author's share of fees was changed.
+		AuthorShareSet {
+			/// The new share.
+			share: Perbill,
+		},
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the share of each transaction fee given to the block author.
+		///
+		/// The dispatch origin must be [`Config::UpdateOrigin`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_author_share())]
+		pub fn set_author_share(origin: OriginFor<T>, share: Perbill) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			AuthorShare::<T>::put(share);
+
+			Self::deposit_event(Event::AuthorShareSet { share });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The author of the block being built, according to its pre-runtime digests.
+	pub fn author() -> Option<T::AccountId> {
+		let digest = frame_system::Pallet::<T>::digest();
+		let pre_runtime_digests = digest.logs().iter().filter_map(|d| d.as_pre_runtime());
+		T::FindAuthor::find_author(pre_runtime_digests)
+	}
+
+	fn distribute(fee: NegativeImbalanceOf<T>, tip: NegativeImbalanceOf<T>) {
+		let author = Self::author();
+		let tip_amount = tip.peek();
+
+		let author_share = AuthorShare::<T>::get() * fee.peek();
+		let (author_fee, treasury_fee) = match author {
+			Some(_) => fee.split(author_share),
+			None => (NegativeImbalanceOf::<T>::zero(), fee),
+		};
+		let (author_fee_amount, treasury_fee_amount) = (author_fee.peek(), treasury_fee.peek());
+
+		let treasury_part = match &author {
+			Some(author) => {
+				T::Currency::resolve_creating(author, author_fee.merge(tip));
+				treasury_fee
+			},
+			None => treasury_fee.merge(tip),
+		};
+		T::Currency::resolve_creating(&T::TreasuryAccount::get(), treasury_part);
+
+		Self::deposit_event(Event::FeesDistributed {
+			author,
+			author_fee: author_fee_amount,
+			treasury_fee: treasury_fee_amount,
+			tip: tip_amount,
+		});
+	}
+
+	/// Distribute a fee and a tip paid in an asset of `F`, like the fees and tips paid in
+	/// [`Config::Currency`].
+	///
+	/// Credits the author cannot receive, e.g. because they are below the minimum balance of the
+	/// asset, go to the treasury, and those the treasury cannot receive either are burnt.
+	pub fn distribute_asset_fees<F>(
+		fee: fungibles::Credit<T::AccountId, F>,
+		tip: fungibles::Credit<T::AccountId, F>,
+	) where
+		F: fungibles::Balanced<T::AccountId, AssetId = T::AssetId, Balance = BalanceOf<T>>,
+	{
+		let asset = fee.asset();
+		let author = Self::author();
+		let tip_amount = tip.peek();
+
+		let author_share = AuthorShare::<T>::get() * fee.peek();
+		let (author_fee, treasury_fee) = match author {
+			Some(_) => fee.split(author_share),
+			None => (fungibles::Credit::<T::AccountId, F>::zero(asset.clone()), fee),
+		};
+		let (author_fee_amount, treasury_fee_amount) = (author_fee.peek(), treasury_fee.peek());
+
+		let treasury = T::TreasuryAccount::get();
+		let resolve = |who: &T::AccountId, credit: fungibles::Credit<T::AccountId, F>| {
+			if let Err(credit) = F::resolve(who, credit) {
+				let _ = F::resolve(&treasury, credit);
+			}
+		};
+		let author_account = author.as_ref().unwrap_or(&treasury);
+		resolve(author_account, author_fee);
+		resolve(author_account, tip);
+		resolve(&treasury, treasury_fee);
+
+		Self::deposit_event(Event::AssetFeesDistributed {
+			asset,
+			author,
+			author_fee: author_fee_amount,
+			treasury_fee: treasury_fee_amount,
+			tip: tip_amount,
+		});
+	}
+}
+
+/// Distributes fees and tips handed over by `pallet_transaction_payment::CurrencyAdapter`.
+pub struct DealWithFees<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for DealWithFees<T> {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalanceOf<T>>) {
+		if let Some(fee) = fees_then_tips.next() {
+			let tip = fees_then_tips.next().unwrap_or_else(NegativeImbalanceOf::<T>::zero);
+			if fee.peek().is_zero() && tip.peek().is_zero() {
+				return
+			}
+			Pallet::<T>::distribute(fee, tip);
+		}
+	}
+}
@@ -0,0 +1,66 @@
+use crate::{mock::*, AuthorShare, DealWithFees, Event};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Currency, OnUnbalanced},
+};
+use sp_runtime::{DispatchError, Perbill};
+
+/// Hand a fee and a tip to the handler, the way `CurrencyAdapter` does.
+fn pay(fee: u64, tip: u64) {
+	DealWithFees::<Test>::on_unbalanceds([Balances::issue(fee), Balances::issue(tip)].into_iter());
+}
+
+#[test]
+fn fees_are_split_and_tips_go_to_author() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		pay(1000, 50);
+
+		// The author gets 20% of the fee and the whole tip.
+		assert_eq!(Balances::free_balance(1), 250);
+		assert_eq!(Balances::free_balance(TREASURY), 800);
+		System::assert_last_event(
+			Event::FeesDistributed { author: Some(1), author_fee: 200, treasury_fee: 800, tip: 50 }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn everything_goes_to_treasury_without_author() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Author::set(None);
+		pay(1000, 50);
+
+		assert_eq!(Balances::free_balance(TREASURY), 1050);
+		System::assert_last_event(
+			Event::FeesDistributed { author: None, author_fee: 0, treasury_fee: 1000, tip: 50 }
+				.into(),
+		);
+	});
+}
+
+#[test]
+fn set_author_share_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			FeeDistribution::set_author_share(RuntimeOrigin::signed(1), Perbill::one()),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(FeeDistribution::set_author_share(
+			RuntimeOrigin::root(),
+			Perbill::from_percent(50)
+		));
+		assert_eq!(AuthorShare::<Test>::get(), Perbill::from_percent(50));
+		System::assert_last_event(
+			Event::AuthorShareSet { share: Perbill::from_percent(50) }.into(),
+		);
+
+		pay(1000, 0);
+		assert_eq!(Balances::free_balance(1), 500);
+		assert_eq!(Balances::free_balance(TREASURY), 500);
+	});
+}
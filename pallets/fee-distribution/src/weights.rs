@@ -0,0 +1,44 @@
+//! Weights for pallet_fee_distribution.
+//!
+//! These weights are estimated by hand from the storage accesses of each call, and are not the
+//! output of a benchmark run. Replace them with the output of the benchmark CLI on reference
+//! hardware:
+//!
+//! ```sh
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_fee_distribution \
+//!   --extrinsic '*' --steps 50 --repeat 20 --wasm-execution compiled \
+//!   --output pallets/fee-distribution/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_fee_distribution.
+pub trait WeightInfo {
+	fn set_author_share() -> Weight;
+}
+
+/// Weights for pallet_fee_distribution using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `FeeDistribution::AuthorShare` (r:0 w:1)
+	/// Proof: `FeeDistribution::AuthorShare` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn set_author_share() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `FeeDistribution::AuthorShare` (r:0 w:1)
+	/// Proof: `FeeDistribution::AuthorShare` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn set_author_share() -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
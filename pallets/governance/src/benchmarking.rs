@@ -0,0 +1,55 @@
+//! Benchmarking setup for pallet-governance
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as Governance;
+use codec::Encode;
+use frame_benchmarking::v2::*;
+use frame_support::traits::{EnsureOrigin, StorePreimage};
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn dispatch_as_root() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RootOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let call: <T as Config>::RuntimeCall =
+			frame_system::Call::remark { remark: Vec::new() }.into();
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Box::new(call));
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn dispatch_preimage_as_root(l: Linear<0, 4_000_000>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::RootOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let call: <T as Config>::RuntimeCall =
+			frame_system::Call::remark { remark: sp_std::vec![0; l as usize] }.into();
+		let encoded = call.encode();
+		let len = encoded.len() as u32;
+		let hash = T::Preimages::note(encoded.into())?;
+		let weight = call.get_dispatch_info().weight;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, hash, len, weight);
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn retire_sudo() -> Result<(), BenchmarkError> {
+		let origin =
+			T::RootOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin);
+
+		assert!(SudoRetired::<T>::get());
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Governance, crate::mock::new_test_ext(), crate::mock::Test);
+}
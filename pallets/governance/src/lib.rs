@@ -0,0 +1,211 @@
+//! # Governance Pallet
+//!
+//! A pallet bridging on-chain collectives and root-level actions.
+//!
+//! ## Overview
+//!
+//! Most privileged calls of the runtime, such as `System::set_code`, require the root origin,
+//! which is otherwise only reachable through `pallet_sudo`. This pallet lets
+//! [`Config::RootOrigin`], typically a combination of collective origins, act as root:
+//!
+//! - [`Pallet::dispatch_as_root`] dispatches a call with the root origin;
+//! - [`Pallet::dispatch_preimage_as_root`] does the same with a call noted as a preimage, so that
+//!   large calls such as runtime upgrades can be uploaded once and referred to by hash, e.g. from
+//!   `pallet_scheduler`;
+//! - [`Pallet::retire_sudo`] removes the sudo key, once and for all.
+//!
+//! Once the sudo key is retired, [`RetiredSudoFilter`] rejects every call of `pallet_sudo`, so
+//! that no new key can be set, not even through [`Pallet::dispatch_as_root`]. Use it as (part of)
+//! the runtime's `BaseCallFilter`.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use codec::DecodeLimit;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	traits::{Contains, IsSubType, IsType, QueryPreimage, StorePreimage, UnfilteredDispatchable},
+};
+use sp_core::H256;
+use sp_runtime::traits::Dispatchable;
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::{pallet_prelude::*, RawOrigin};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_sudo::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The overarching call type.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo
+			+ From<frame_system::Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+		/// The origin allowed to act as root.
+		type RootOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+		/// Where [`Pallet::dispatch_preimage_as_root`] looks up calls.
+		type Preimages: QueryPreimage + StorePreimage;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Whether the sudo key was retired through [`Pallet::retire_sudo`].
+	#[pallet::storage]
+	pub type SudoRetired<T> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A call was dispatched with the root origin.
+		DispatchedAsRoot {
+			/// The result of the call.
+			result: DispatchResult,
+		},
+		/// The sudo key was removed for good.
+		SudoRetired,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The sudo key was already retired.
+		SudoAlreadyRetired,
+		/// The preimage of the call is not available.
+		UnavailablePreImage,
+		/// The preimage could not be decoded as a call.
+		UndecodableCall,
+		/// The weight of the call exceeds the given witness.
+		InvalidCallWeightWitness,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Dispatch a call with the root origin.
+		///
+		/// The dispatch origin must be [`Config::RootOrigin`]. Unlike calls dispatched by
+		/// `pallet_sudo`, the call must pass the runtime's `BaseCallFilter`.
+		#[pallet::call_index(0)]
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			(
+				<T as Config>::WeightInfo::dispatch_as_root().saturating_add(dispatch_info.weight),
+				dispatch_info.class,
+			)
+		})]
+		pub fn dispatch_as_root(
+			origin: OriginFor<T>,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
+			T::RootOrigin::ensure_origin(origin)?;
+
+			Self::do_dispatch_as_root(*call)
+		}
+
+		/// Remove the sudo key. This cannot be undone: no sudo key can be set afterwards.
+		///
+		/// The dispatch origin must be [`Config::RootOrigin`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::retire_sudo())]
+		pub fn retire_sudo(origin: OriginFor<T>) -> DispatchResult {
+			T::RootOrigin::ensure_origin(origin)?;
+			ensure!(!SudoRetired::<T>::get(), Error::<T>::SudoAlreadyRetired);
+
+			pallet_sudo::Call::<T>::remove_key {}
+				.dispatch_bypass_filter(RawOrigin::Root.into())
+				.map_err(|e| e.error)?;
+			SudoRetired::<T>::put(true);
+
+			Self::deposit_event(Event::SudoRetired);
+			Ok(())
+		}
+
+		/// Dispatch with the root origin the call noted as the preimage `hash` of length `len`.
+		///
+		/// The dispatch origin must be [`Config::RootOrigin`]. `call_weight_witness` must be at
+		/// least the weight of the call, which is only known once the preimage is fetched. The
+		/// preimage is left in place: its depositor may unnote it afterwards.
+		#[pallet::call_index(2)]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::dispatch_preimage_as_root(*len)
+				.saturating_add(*call_weight_witness)
+		)]
+		pub fn dispatch_preimage_as_root(
+			origin: OriginFor<T>,
+			hash: H256,
+			len: u32,
+			call_weight_witness: Weight,
+		) -> DispatchResultWithPostInfo {
+			T::RootOrigin::ensure_origin(origin)?;
+
+			let encoded = T::Preimages::fetch(&hash, Some(len))
+				.map_err(|_| Error::<T>::UnavailablePreImage)?;
+			let call = <T as Config>::RuntimeCall::decode_all_with_depth_limit(
+				CALL_DECODE_DEPTH_LIMIT,
+				&mut &encoded[..],
+			)
+			.map_err(|_| Error::<T>::UndecodableCall)?;
+			ensure!(
+				call.get_dispatch_info().weight.all_lte(call_weight_witness),
+				Error::<T>::InvalidCallWeightWitness
+			);
+
+			Self::do_dispatch_as_root(call)
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn do_dispatch_as_root(call: <T as Config>::RuntimeCall) -> DispatchResultWithPostInfo {
+			ensure!(
+				<T as frame_system::Config>::BaseCallFilter::contains(call.into_ref()),
+				frame_system::Error::<T>::CallFiltered
+			);
+
+			let result = call.dispatch(RawOrigin::Root.into());
+
+			Self::deposit_event(Event::DispatchedAsRoot {
+				result: result.map(|_| ()).map_err(|e| e.error),
+			});
+			// Like `pallet_sudo`, no fee is charged for the dispatch.
+			Ok(Pays::No.into())
+		}
+	}
+}
+
+/// The maximum nesting depth of calls decoded from preimages.
+const CALL_DECODE_DEPTH_LIMIT: u32 = 256;
+
+/// Rejects every call of `pallet_sudo` once the sudo key has been retired.
+pub struct RetiredSudoFilter<T>(core::marker::PhantomData<T>);
+
+impl<T: Config> Contains<<T as frame_system::Config>::RuntimeCall> for RetiredSudoFilter<T>
+where
+	<T as frame_system::Config>::RuntimeCall: IsSubType<pallet_sudo::Call<T>>,
+{
+	fn contains(call: &<T as frame_system::Config>::RuntimeCall) -> bool {
+		!(SudoRetired::<T>::get() && call.is_sub_type().is_some())
+	}
+}
@@ -0,0 +1,149 @@
+use crate::{mock::*, Error, Event, RetiredSudoFilter, SudoRetired};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, traits::Contains};
+use sp_core::H256;
+use sp_runtime::DispatchError;
+
+fn remark() -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] }))
+}
+
+fn set_sudo_key(new: u64) -> Box<RuntimeCall> {
+	Box::new(RuntimeCall::Sudo(pallet_sudo::Call::set_key { new }))
+}
+
+#[test]
+fn dispatch_as_root_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Governance::dispatch_as_root(RuntimeOrigin::signed(SUDO), remark()),
+			DispatchError::BadOrigin
+		);
+
+		// `remark_with_event` does not need root, but `set_key` does.
+		assert_ok!(Governance::dispatch_as_root(RuntimeOrigin::signed(Council::get()), remark()));
+		assert_ok!(Governance::dispatch_as_root(
+			RuntimeOrigin::signed(Council::get()),
+			set_sudo_key(2)
+		));
+		assert_eq!(pallet_sudo::Pallet::<Test>::key(), Some(2));
+		System::assert_last_event(Event::DispatchedAsRoot { result: Ok(()) }.into());
+	});
+}
+
+#[test]
+fn dispatch_as_root_reports_call_errors() {
+	new_test_ext().execute_with(|| {
+		let failing = Box::new(RuntimeCall::Governance(crate::Call::retire_sudo {}));
+		// The nested `retire_sudo` is dispatched as root and succeeds...
+		assert_ok!(Governance::dispatch_as_root(RuntimeOrigin::root(), failing.clone()));
+		// ... so a second one fails, which is reported in the event.
+		assert_ok!(Governance::dispatch_as_root(RuntimeOrigin::root(), failing));
+		System::assert_last_event(
+			Event::DispatchedAsRoot { result: Err(Error::<Test>::SudoAlreadyRetired.into()) }
+				.into(),
+		);
+	});
+}
+
+/// Note `call` as a preimage, returning its hash, length and weight.
+fn note_call(call: &RuntimeCall) -> (H256, u32, frame_support::weights::Weight) {
+	let encoded = call.encode();
+	let hash = H256(sp_io::hashing::blake2_256(&encoded));
+	assert_ok!(Preimage::note_preimage(RuntimeOrigin::root(), encoded.clone()));
+	(hash, encoded.len() as u32, call.get_dispatch_info().weight)
+}
+
+#[test]
+fn dispatch_preimage_as_root_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Governance::dispatch_preimage_as_root(
+				RuntimeOrigin::root(),
+				H256::zero(),
+				1,
+				Default::default()
+			),
+			Error::<Test>::UnavailablePreImage
+		);
+
+		let (hash, len, weight) = note_call(&set_sudo_key(2));
+		assert_noop!(
+			Governance::dispatch_preimage_as_root(RuntimeOrigin::signed(SUDO), hash, len, weight),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Governance::dispatch_preimage_as_root(
+				RuntimeOrigin::signed(Council::get()),
+				hash,
+				len,
+				weight.saturating_sub(frame_support::weights::Weight::from_parts(1, 0))
+			),
+			Error::<Test>::InvalidCallWeightWitness
+		);
+
+		assert_ok!(Governance::dispatch_preimage_as_root(
+			RuntimeOrigin::signed(Council::get()),
+			hash,
+			len,
+			weight
+		));
+		assert_eq!(pallet_sudo::Pallet::<Test>::key(), Some(2));
+		System::assert_last_event(Event::DispatchedAsRoot { result: Ok(()) }.into());
+	});
+}
+
+#[test]
+fn dispatch_preimage_as_root_rejects_undecodable_preimages() {
+	new_test_ext().execute_with(|| {
+		let garbage = vec![0xff; 8];
+		let hash = H256(sp_io::hashing::blake2_256(&garbage));
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::root(), garbage));
+
+		assert_noop!(
+			Governance::dispatch_preimage_as_root(
+				RuntimeOrigin::root(),
+				hash,
+				8,
+				Default::default()
+			),
+			Error::<Test>::UndecodableCall
+		);
+	});
+}
+
+#[test]
+fn retire_sudo_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Governance::retire_sudo(RuntimeOrigin::signed(SUDO)),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Governance::retire_sudo(RuntimeOrigin::signed(Council::get())));
+		assert!(SudoRetired::<Test>::get());
+		assert_eq!(pallet_sudo::Pallet::<Test>::key(), None);
+		System::assert_last_event(Event::SudoRetired.into());
+
+		assert_noop!(
+			Governance::retire_sudo(RuntimeOrigin::signed(Council::get())),
+			Error::<Test>::SudoAlreadyRetired
+		);
+	});
+}
+
+#[test]
+fn retired_sudo_cannot_come_back() {
+	new_test_ext().execute_with(|| {
+		assert!(RetiredSudoFilter::<Test>::contains(&set_sudo_key(2)));
+		assert_ok!(Governance::retire_sudo(RuntimeOrigin::root()));
+		assert!(!RetiredSudoFilter::<Test>::contains(&set_sudo_key(2)));
+		assert!(RetiredSudoFilter::<Test>::contains(&remark()));
+
+		assert_noop!(
+			Governance::dispatch_as_root(RuntimeOrigin::root(), set_sudo_key(2)),
+			frame_system::Error::<Test>::CallFiltered
+		);
+		assert_eq!(pallet_sudo::Pallet::<Test>::key(), None);
+	});
+}
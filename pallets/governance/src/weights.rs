@@ -0,0 +1,111 @@
+//! Weights for pallet_governance.
+//!
+//! These weights are estimated by hand from the storage accesses of each call, and are not the
+//! output of a benchmark run. The reads of `SafeMode` and `TxPause` are those of the
+//! `BaseCallFilter` of the node template runtime, which the dispatching calls evaluate. Replace
+//! the weights with the output of the benchmark CLI on reference hardware:
+//!
+//! ```sh
+//! ./target/release/node-template benchmark pallet --chain dev --pallet pallet_governance \
+//!   --extrinsic '*' --steps 50 --repeat 20 --wasm-execution compiled \
+//!   --output pallets/governance/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_governance.
+pub trait WeightInfo {
+	fn dispatch_as_root() -> Weight;
+	fn retire_sudo() -> Weight;
+	fn dispatch_preimage_as_root(l: u32, ) -> Weight;
+}
+
+/// Weights for pallet_governance using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Governance::SudoRetired` (r:1 w:0)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `SafeMode::EnteredUntil` (r:1 w:0)
+	/// Proof: `SafeMode::EnteredUntil` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `TxPause::PausedCalls` (r:2 w:0)
+	/// Proof: `TxPause::PausedCalls` (`max_values`: None, `max_size`: Some(532), added: 3007, mode: `MaxEncodedLen`)
+	fn dispatch_as_root() -> Weight {
+		Weight::from_parts(10_000_000, 3997)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+	}
+	/// Storage: `Governance::SudoRetired` (r:1 w:1)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `Sudo::Key` (r:1 w:1)
+	/// Proof: `Sudo::Key` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn retire_sudo() -> Weight {
+		Weight::from_parts(16_000_000, 1517)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Preimage::StatusFor` (r:1 w:0)
+	/// Proof: `Preimage::StatusFor` (`max_values`: None, `max_size`: Some(91), added: 2566, mode: `MaxEncodedLen`)
+	/// Storage: `Preimage::RequestStatusFor` (r:1 w:0)
+	/// Proof: `Preimage::RequestStatusFor` (`max_values`: None, `max_size`: Some(91), added: 2566, mode: `MaxEncodedLen`)
+	/// Storage: `Preimage::PreimageFor` (r:1 w:0)
+	/// Proof: `Preimage::PreimageFor` (`max_values`: None, `max_size`: Some(4194344), added: 4196819, mode: `Measured`)
+	/// Storage: `Governance::SudoRetired` (r:1 w:0)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `SafeMode::EnteredUntil` (r:1 w:0)
+	/// Proof: `SafeMode::EnteredUntil` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `TxPause::PausedCalls` (r:2 w:0)
+	/// Proof: `TxPause::PausedCalls` (`max_values`: None, `max_size`: Some(532), added: 3007, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 4000000]`.
+	fn dispatch_preimage_as_root(l: u32, ) -> Weight {
+		Weight::from_parts(23_000_000, 3997)
+			.saturating_add(Weight::from_parts(1_412, 0).saturating_mul(l.into()))
+			.saturating_add(T::DbWeight::get().reads(7_u64))
+			.saturating_add(Weight::from_parts(0, 1).saturating_mul(l.into()))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `Governance::SudoRetired` (r:1 w:0)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `SafeMode::EnteredUntil` (r:1 w:0)
+	/// Proof: `SafeMode::EnteredUntil` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `TxPause::PausedCalls` (r:2 w:0)
+	/// Proof: `TxPause::PausedCalls` (`max_values`: None, `max_size`: Some(532), added: 3007, mode: `MaxEncodedLen`)
+	fn dispatch_as_root() -> Weight {
+		Weight::from_parts(10_000_000, 3997)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+	}
+	/// Storage: `Governance::SudoRetired` (r:1 w:1)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `Sudo::Key` (r:1 w:1)
+	/// Proof: `Sudo::Key` (`max_values`: Some(1), `max_size`: Some(32), added: 527, mode: `MaxEncodedLen`)
+	fn retire_sudo() -> Weight {
+		Weight::from_parts(16_000_000, 1517)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `Preimage::StatusFor` (r:1 w:0)
+	/// Proof: `Preimage::StatusFor` (`max_values`: None, `max_size`: Some(91), added: 2566, mode: `MaxEncodedLen`)
+	/// Storage: `Preimage::RequestStatusFor` (r:1 w:0)
+	/// Proof: `Preimage::RequestStatusFor` (`max_values`: None, `max_size`: Some(91), added: 2566, mode: `MaxEncodedLen`)
+	/// Storage: `Preimage::PreimageFor` (r:1 w:0)
+	/// Proof: `Preimage::PreimageFor` (`max_values`: None, `max_size`: Some(4194344), added: 4196819, mode: `Measured`)
+	/// Storage: `Governance::SudoRetired` (r:1 w:0)
+	/// Proof: `Governance::SudoRetired` (`max_values`: Some(1), `max_size`: Some(1), added: 496, mode: `MaxEncodedLen`)
+	/// Storage: `SafeMode::EnteredUntil` (r:1 w:0)
+	/// Proof: `SafeMode::EnteredUntil` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	/// Storage: `TxPause::PausedCalls` (r:2 w:0)
+	/// Proof: `TxPause::PausedCalls` (`max_values`: None, `max_size`: Some(532), added: 3007, mode: `MaxEncodedLen`)
+	/// The range of component `l` is `[0, 4000000]`.
+	fn dispatch_preimage_as_root(l: u32, ) -> Weight {
+		Weight::from_parts(23_000_000, 3997)
+			.saturating_add(Weight::from_parts(1_412, 0).saturating_mul(l.into()))
+			.saturating_add(RocksDbWeight::get().reads(7_u64))
+			.saturating_add(Weight::from_parts(0, 1).saturating_mul(l.into()))
+	}
+}
@@ -0,0 +1,91 @@
+//! RPC interface for the template pallet.
+//!
+//! Exposes the [`TemplateApi`] runtime API over JSON-RPC so that the pallet's storage can be read
+//! as typed values at any block, instead of through raw storage queries.
+
+#![warn(missing_docs)]
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_template_runtime_api::TemplateApi as TemplateRuntimeApi;
+
+/// Template RPC methods.
+#[rpc(client, server)]
+pub trait TemplateApi<BlockHash, AccountId> {
+	/// Returns the global `Something` value at the given block, or at the best block if `at` is
+	/// omitted.
+	#[method(name = "template_getSomething")]
+	fn get_something(&self, at: Option<BlockHash>) -> RpcResult<Option<u32>>;
+
+	/// Returns the values last set by `account` at the given block, oldest first.
+	#[method(name = "template_getHistory")]
+	fn get_history(&self, account: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<u32>>;
+}
+
+/// Error codes returned by the template RPC methods.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// Provides RPC methods to query the template pallet.
+pub struct Template<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Template<C, B> {
+	/// Creates a new instance of the Template RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId> TemplateApiServer<<Block as BlockT>::Hash, AccountId>
+	for Template<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn get_something(&self, at: Option<Block::Hash>) -> RpcResult<Option<u32>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.get_something(at_hash)
+			.map_err(|e| runtime_error("Unable to query `Something`.", e))
+	}
+
+	fn get_history(&self, account: AccountId, at: Option<Block::Hash>) -> RpcResult<Vec<u32>> {
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.get_history(at_hash, account)
+			.map_err(|e| runtime_error("Unable to query the account history.", e))
+	}
+}
+
+fn runtime_error(message: &str, error: sp_api::ApiError) -> ErrorObjectOwned {
+	ErrorObject::owned(Error::RuntimeError.into(), message, Some(error.to_string()))
+}
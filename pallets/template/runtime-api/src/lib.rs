@@ -0,0 +1,23 @@
+//! Runtime API definition for the template pallet.
+//!
+//! The API is implemented by the runtime in `impl_runtime_apis!` and queried by the node through
+//! the `template_*` RPC methods of the `pallet-template-rpc` crate.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Typed access to the storage of the template pallet.
+	pub trait TemplateApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// The value of the global `Something` storage item, if set.
+		fn get_something() -> Option<u32>;
+
+		/// The values last set by `account`, oldest first.
+		fn get_history(account: AccountId) -> Vec<u32>;
+	}
+}
@@ -5,8 +5,18 @@ use super::*;
 #[allow(unused)]
 use crate::Pallet as Template;
 use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
 use frame_system::RawOrigin;
 
+/// Fill the history of `who` up to `T::MaxHistory`, which is the worst case for both setting and
+/// clearing a value.
+fn fill_history<T: Config>(who: &T::AccountId) {
+	let history =
+		BoundedVec::<u32, T::MaxHistory>::truncate_from((0..T::MaxHistory::get()).collect());
+	History::<T>::insert(who, history);
+	AccountSomething::<T>::insert(who, T::MaxHistory::get());
+}
+
 #[benchmarks]
 mod benchmarks {
 	use super::*;
@@ -15,20 +25,57 @@ mod benchmarks {
 	fn do_something() {
 		let value = 100u32.into();
 		let caller: T::AccountId = whitelisted_caller();
+		fill_history::<T>(&caller);
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), value);
+		do_something(RawOrigin::Signed(caller.clone()), value);
 
-		assert_eq!(Something::<T>::get(), Some(value));
+		assert_eq!(Template::<T>::something(), Some(value));
+		assert_eq!(AccountSomething::<T>::get(&caller), Some(value));
+		assert_eq!(History::<T>::get(&caller).last(), Some(&value));
 	}
 
 	#[benchmark]
 	fn cause_error() {
-		Something::<T>::put(100u32);
+		Something::<T>::put(SomethingInfo { value: 100, updated_at: 0u32.into() });
 		let caller: T::AccountId = whitelisted_caller();
 		#[extrinsic_call]
 		cause_error(RawOrigin::Signed(caller));
 
-		assert_eq!(Something::<T>::get(), Some(101u32));
+		assert_eq!(Template::<T>::something(), Some(101u32));
+	}
+
+	#[benchmark]
+	fn clear_something() {
+		let caller: T::AccountId = whitelisted_caller();
+		fill_history::<T>(&caller);
+		#[extrinsic_call]
+		clear_something(RawOrigin::Signed(caller.clone()));
+
+		assert_eq!(AccountSomething::<T>::get(&caller), None);
+		assert!(History::<T>::get(&caller).is_empty());
+	}
+
+	#[benchmark]
+	fn submit_value_signed() {
+		let caller: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		submit_value_signed(RawOrigin::Signed(caller), 100);
+
+		assert_eq!(Template::<T>::something(), Some(100));
+	}
+
+	// The signature of the payload is checked when validating the transaction, not when
+	// dispatching it, so the dispatch is benchmarked without a signed payload.
+	#[benchmark]
+	fn submit_value_unsigned() {
+		#[block]
+		{
+			Template::<T>::store_submitted(None, 100);
+		}
+
+		assert_eq!(Template::<T>::something(), Some(100));
+		let now = frame_system::Pallet::<T>::block_number();
+		assert_eq!(NextUnsignedAt::<T>::get(), now + T::UnsignedInterval::get());
 	}
 
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
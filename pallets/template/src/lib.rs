@@ -7,12 +7,17 @@
 //! ## Overview
 //!
 //! This template pallet contains basic examples of:
-//! - declaring a storage item that stores a single `u32` value
+//! - declaring a storage item that stores a single `u32` value, along with the block it was set at
+//! - declaring storage maps keyed by account, including a bounded history of values
 //! - declaring and using events
 //! - declaring and using errors
 //! - a dispatchable function that allows a user to set a new value to storage and emits an event
 //!   upon success
+//! - a dispatchable function that clears the values stored for the caller
 //! - another dispatchable function that causes a custom error to be thrown
+//! - an offchain worker submitting values back on chain with signed transactions and unsigned
+//!   transactions with signed payloads, see [`offchain`]
+//! - versioning the storage layout and migrating it on runtime upgrade, see [`migrations`]
 //!
 //! Each pallet section is annotated with an attribute using the `#[pallet::...]` procedural macro.
 //! This macro generates the necessary code for a pallet to be aggregated into a FRAME runtime.
@@ -39,6 +44,9 @@
 // We make sure this pallet uses `no_std` for compiling to Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "try-runtime")]
+extern crate alloc;
+
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
@@ -57,6 +65,8 @@ mod tests;
 // for each dispatchable and generates this pallet's weight.rs file. Learn more about benchmarking here: https://docs.substrate.io/test/benchmark/
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod offchain;
 pub mod weights;
 pub use weights::*;
 
@@ -65,12 +75,30 @@ pub use weights::*;
 pub mod pallet {
 	// Import various useful types required by all FRAME pallets.
 	use super::*;
-	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use crate::offchain::{ValuePayload, ValueSource};
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+			SignedPayload, Signer,
+		},
+		pallet_prelude::*,
+	};
+	use sp_runtime::traits::Zero;
+
+	/// The log target of this pallet.
+	const LOG_TARGET: &str = "runtime::template";
+
+	/// The in-code storage version.
+	///
+	/// Bump it whenever the layout of a storage item changes, along with a migration in
+	/// [`crate::migrations`].
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
 	// The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
 	// (`Call`s) in this pallet.
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The pallet's configuration trait.
@@ -79,19 +107,84 @@ pub mod pallet {
 	/// These types are defined generically and made concrete when the pallet is declared in the
 	/// `runtime/src/lib.rs` file of your chain.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// The overarching runtime event type.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		/// A type representing the weights required by the dispatchables of this pallet.
 		type WeightInfo: WeightInfo;
+		/// The number of past values kept in each account's [`History`].
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
+		/// The keys signing the transactions of the offchain worker, e.g.
+		/// [`crate::offchain::crypto::TemplateAuthId`].
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// The keys allowed to sign the payloads of the unsigned transactions of the offchain
+		/// worker, e.g. those of the current validators.
+		type UnsignedSigners: Contains<Self::Public>;
+		/// Where the offchain worker reads the values to submit from.
+		type ValueSource: ValueSource;
+		/// The minimum number of blocks between two unsigned transactions of the offchain worker.
+		#[pallet::constant]
+		type UnsignedInterval: Get<BlockNumberFor<Self>>;
+		/// The priority of the unsigned transactions of the offchain worker.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+	}
+
+	/// The value stored in [`Something`].
+	#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+	pub struct SomethingInfo<BlockNumber> {
+		/// The value itself.
+		pub value: u32,
+		/// The block at which the value was last changed.
+		pub updated_at: BlockNumber,
 	}
 
 	/// A storage item for this pallet.
 	///
 	/// In this template, we are declaring a storage item called `Something` that stores a single
-	/// `u32` value. Learn more about runtime storage here: <https://docs.substrate.io/build/runtime-storage/>
+	/// `u32` value, along with the block it was last changed at. Learn more about runtime storage
+	/// here: <https://docs.substrate.io/build/runtime-storage/>
+	///
+	/// Up to storage version 0, it stored the bare `u32` value, see [`crate::migrations::v1`].
+	#[pallet::storage]
+	pub type Something<T: Config> = StorageValue<_, SomethingInfo<BlockNumberFor<T>>>;
+
+	/// The latest value set by each account.
+	///
+	/// Unlike [`Something`], which is shared by everyone, each account only ever overwrites its
+	/// own entry.
+	#[pallet::storage]
+	pub type AccountSomething<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32>;
+
+	/// The last [`Config::MaxHistory`] values set by each account, oldest first.
+	///
+	/// Once full, setting a new value drops the oldest one.
+	#[pallet::storage]
+	pub type History<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u32, T::MaxHistory>, ValueQuery>;
+
+	/// The first block at which an unsigned transaction of the offchain worker is accepted.
 	#[pallet::storage]
-	pub type Something<T> = StorageValue<_, u32>;
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial value of [`Something`], if any.
+		pub something: Option<u32>,
+		#[serde(skip)]
+		pub _config: core::marker::PhantomData<T>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(value) = self.something {
+				Pallet::<T>::put_something(value);
+			}
+		}
+	}
 
 	/// Events that functions in this pallet can emit.
 	///
@@ -108,10 +201,26 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// A user has successfully set a new value.
 		SomethingStored {
-			/// The new value set.
-			something: u32,
 			/// The account who set the new value.
 			who: T::AccountId,
+			/// The value previously set by the account, if any.
+			old: Option<u32>,
+			/// The new value set.
+			new: u32,
+		},
+		/// A user has cleared their value and history.
+		SomethingCleared {
+			/// The account who cleared their value.
+			who: T::AccountId,
+			/// The value set by the account before it was cleared.
+			old: u32,
+		},
+		/// The offchain worker has submitted a new value.
+		ValueSubmitted {
+			/// The account who signed the transaction, or `None` for unsigned transactions.
+			who: Option<T::AccountId>,
+			/// The new value set.
+			value: u32,
 		},
 	}
 
@@ -131,6 +240,30 @@ pub mod pallet {
 		StorageOverflow,
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Submit the value of [`Config::ValueSource`] back on chain if it changed.
+		///
+		/// It runs after the import of every block, with access to the offchain APIs and to the
+		/// keystore of the node. Even blocks submit a signed transaction, odd ones an unsigned
+		/// transaction with a signed payload.
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let Some(value) = T::ValueSource::value() else { return };
+			if Self::something() == Some(value) {
+				return
+			}
+
+			let result = if (block_number % 2u32.into()).is_zero() {
+				Self::submit_signed(value)
+			} else {
+				Self::submit_unsigned(block_number, value)
+			};
+			if let Err(e) = result {
+				log::warn!(target: LOG_TARGET, "Cannot submit {value} at block {block_number:?}: {e}");
+			}
+		}
+	}
+
 	/// The pallet's dispatchable functions ([`Call`]s).
 	///
 	/// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -148,6 +281,9 @@ pub mod pallet {
 		/// An example dispatchable that takes a single u32 value as a parameter, writes the value
 		/// to storage and emits an event.
 		///
+		/// The value is written both to the global [`Something`] item and to the caller's entry in
+		/// [`AccountSomething`], and appended to the caller's [`History`].
+		///
 		/// It checks that the _origin_ for this call is _Signed_ and returns a dispatch
 		/// error if it isn't. Learn more about origins here: <https://docs.substrate.io/build/origins/>
 		#[pallet::call_index(0)]
@@ -157,10 +293,15 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 
 			// Update storage.
-			Something::<T>::put(something);
+			Self::put_something(something);
+			let old = AccountSomething::<T>::mutate(&who, |value| value.replace(something));
+			History::<T>::mutate(&who, |history| {
+				// Append the new value, dropping the oldest one if the history is full.
+				let _ = history.force_insert_keep_right(history.len(), something);
+			});
 
 			// Emit an event.
-			Self::deposit_event(Event::SomethingStored { something, who });
+			Self::deposit_event(Event::SomethingStored { who, old, new: something });
 
 			// Return a successful `DispatchResult`
 			Ok(())
@@ -191,12 +332,142 @@ pub mod pallet {
 				Some(old) => {
 					// Increment the value read from storage. This will cause an error in the event
 					// of overflow.
-					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+					let new = old.value.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
 					// Update the value in storage with the incremented result.
-					Something::<T>::put(new);
+					Self::put_something(new);
 					Ok(())
 				},
 			}
 		}
+
+		/// Clear the value and history stored for the caller.
+		///
+		/// The global [`Something`] item is left untouched.
+		///
+		/// ## Errors
+		///
+		/// - If the caller has not set a value ([`Error::NoneValue`])
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let old = AccountSomething::<T>::take(&who).ok_or(Error::<T>::NoneValue)?;
+			History::<T>::remove(&who);
+
+			Self::deposit_event(Event::SomethingCleared { who, old });
+			Ok(())
+		}
+
+		/// Set [`Something`] to a value submitted by the offchain worker, with a signed
+		/// transaction.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_value_signed())]
+		pub fn submit_value_signed(origin: OriginFor<T>, value: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::store_submitted(Some(who), value);
+			Ok(())
+		}
+
+		/// Set [`Something`] to a value submitted by the offchain worker, with an unsigned
+		/// transaction.
+		///
+		/// The payload and its signature are checked by [`Pallet::validate_unsigned`], which only
+		/// accepts one such transaction every [`Config::UnsignedInterval`] blocks.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::submit_value_unsigned())]
+		pub fn submit_value_unsigned(
+			origin: OriginFor<T>,
+			payload: ValuePayload<T::Public, BlockNumberFor<T>>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			Self::store_submitted(None, payload.value);
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Accept the unsigned transactions of the offchain worker whose payload is signed by the
+		/// key it names, one of [`Config::UnsignedSigners`], from a block past [`NextUnsignedAt`].
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::submit_value_unsigned { payload, signature } = call else {
+				return InvalidTransaction::Call.into()
+			};
+			if !T::UnsignedSigners::contains(&payload.public) {
+				return InvalidTransaction::BadSigner.into()
+			}
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
+
+			let next_unsigned_at = NextUnsignedAt::<T>::get();
+			if payload.block_number < next_unsigned_at {
+				return InvalidTransaction::Stale.into()
+			}
+			if payload.block_number > frame_system::Pallet::<T>::block_number() {
+				return InvalidTransaction::Future.into()
+			}
+
+			ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				// Only one transaction is accepted per interval.
+				.and_provides(next_unsigned_at)
+				.longevity(T::UnsignedInterval::get().try_into().unwrap_or(u64::MAX))
+				.propagate(true)
+				.build()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The current value of [`Something`], if any.
+		pub fn something() -> Option<u32> {
+			Something::<T>::get().map(|info| info.value)
+		}
+
+		/// Store a value submitted by the offchain worker, by `who` or unsigned.
+		pub(crate) fn store_submitted(who: Option<T::AccountId>, value: u32) {
+			Self::put_something(value);
+			if who.is_none() {
+				let now = frame_system::Pallet::<T>::block_number();
+				NextUnsignedAt::<T>::put(now + T::UnsignedInterval::get());
+			}
+			Self::deposit_event(Event::ValueSubmitted { who, value });
+		}
+
+		/// Submit `value` with a signed transaction from any account of the keystore.
+		fn submit_signed(value: u32) -> Result<(), &'static str> {
+			let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+				.send_signed_transaction(|_| Call::submit_value_signed { value })
+				.ok_or("no local account to sign with")?;
+			result.map_err(|()| "the signed transaction was rejected")
+		}
+
+		/// Submit `value` with an unsigned transaction, its payload signed by any key of the
+		/// keystore.
+		fn submit_unsigned(
+			block_number: BlockNumberFor<T>,
+			value: u32,
+		) -> Result<(), &'static str> {
+			if block_number < NextUnsignedAt::<T>::get() {
+				return Err("too early for an unsigned transaction")
+			}
+			let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+				.send_unsigned_transaction(
+					|account| ValuePayload { block_number, value, public: account.public.clone() },
+					|payload, signature| Call::submit_value_unsigned { payload, signature },
+				)
+				.ok_or("no local key to sign with")?;
+			result.map_err(|()| "the unsigned transaction was rejected")
+		}
+
+		/// Set [`Something`] to `value`, changed at the current block.
+		fn put_something(value: u32) {
+			let updated_at = frame_system::Pallet::<T>::block_number();
+			Something::<T>::put(SomethingInfo { value, updated_at });
+		}
 	}
 }
@@ -0,0 +1,81 @@
+//! Storage migrations of the template pallet.
+//!
+//! Each storage version has its own module, holding the migration from the previous version.
+//! Migrations are wrapped in [`VersionedMigration`], which only runs them when the on-chain
+//! storage version matches the version they migrate from, and bumps it afterwards. A migration
+//! left in the runtime after it ran is therefore a no-op.
+//!
+//! With the `try-runtime` feature, migrations also implement `pre_upgrade` and `post_upgrade`,
+//! which check their effect when rehearsing a runtime upgrade against a snapshot of the chain
+//! state.
+
+use crate::{Config, Pallet};
+use frame_support::migrations::VersionedMigration;
+
+/// Version 1: [`crate::Something`] stores a [`crate::SomethingInfo`], recording the block the value
+/// was last changed at, instead of the bare `u32` value.
+pub mod v1 {
+	use super::*;
+	use crate::{Something, SomethingInfo};
+	use frame_support::{
+		pallet_prelude::*, storage_alias, traits::OnRuntimeUpgrade, weights::Weight,
+	};
+
+	#[cfg(feature = "try-runtime")]
+	use alloc::vec::Vec;
+	#[cfg(feature = "try-runtime")]
+	use sp_runtime::TryRuntimeError;
+
+	/// The storage items of version 0.
+	pub mod v0 {
+		use super::*;
+
+		/// [`crate::Something`] as of version 0, storing the bare value.
+		#[storage_alias]
+		pub type Something<T: Config> = StorageValue<Pallet<T>, u32>;
+	}
+
+	/// Move [`v0::Something`] into the layout of version 1, without checking the storage version.
+	///
+	/// The block the value was last changed at is unknown, so it is set to the block of the
+	/// migration. Use [`MigrateV0ToV1`] instead.
+	pub struct UncheckedMigrateV0ToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for UncheckedMigrateV0ToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			// Both items share the same storage key, so taking the old value leaves room for the
+			// new one.
+			match v0::Something::<T>::take() {
+				Some(value) => {
+					let updated_at = frame_system::Pallet::<T>::block_number();
+					Something::<T>::put(SomethingInfo { value, updated_at });
+					T::DbWeight::get().reads_writes(2, 2)
+				},
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			Ok(v0::Something::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			let old = Option::<u32>::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state cannot be decoded")?;
+			let new = Something::<T>::get().map(|info| info.value);
+			ensure!(old == new, "`Something` changed during the migration");
+			Ok(())
+		}
+	}
+
+	/// Migrate [`crate::Something`] from version 0 to version 1.
+	pub type MigrateV0ToV1<T> = VersionedMigration<
+		0,
+		1,
+		UncheckedMigrateV0ToV1<T>,
+		Pallet<T>,
+		<T as frame_system::Config>::DbWeight,
+	>;
+}
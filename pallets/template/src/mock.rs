@@ -1,15 +1,34 @@
 use crate as pallet_template;
 use frame_support::{
-	derive_impl,
-	traits::{ConstU16, ConstU64},
+	derive_impl, parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64, Contains},
+};
+use frame_system::offchain::{
+	AppCrypto, CreateSignedTransaction, SendTransactionTypes, SigningTypes,
+};
+use parking_lot::RwLock;
+use sp_core::{
+	offchain::{
+		testing::{PoolState, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	},
+	H256,
 };
-use sp_core::H256;
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
 	BuildStorage,
 };
+use std::sync::Arc;
 
 type Block = frame_system::mocking::MockBlock<Test>;
+pub type Extrinsic = TestXt<RuntimeCall, ()>;
+
+/// The account, and key, of the offchain worker in [`new_offchain_test_ext`].
+pub const AUTHORITY: u64 = 7;
+
+/// The persistent offchain storage key of the values submitted by the offchain worker.
+pub const VALUE_KEY: &[u8] = b"template::value";
 
 // Configure a mock runtime to test the pallet.
 frame_support::construct_runtime!(
@@ -47,12 +66,81 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+parameter_types! {
+	pub const ValueKey: &'static [u8] = VALUE_KEY;
+}
+
 impl pallet_template::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
+	type MaxHistory = ConstU32<3>;
+	type AuthorityId = TestAuthId;
+	type UnsignedSigners = UnsignedSigners;
+	type ValueSource = pallet_template::offchain::LocalStorageSource<ValueKey>;
+	type UnsignedInterval = ConstU64<5>;
+	type UnsignedPriority = ConstU64<100>;
+}
+
+/// Only [`AUTHORITY`] signs the payloads of unsigned transactions.
+pub struct UnsignedSigners;
+
+impl Contains<UintAuthorityId> for UnsignedSigners {
+	fn contains(public: &UintAuthorityId) -> bool {
+		*public == UintAuthorityId(AUTHORITY)
+	}
+}
+
+/// Signs with the keys set by `UintAuthorityId::set_all_keys`, without a keystore.
+pub struct TestAuthId;
+
+impl AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+impl SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> CreateSignedTransaction<LocalCall> for Test
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		_public: UintAuthorityId,
+		account: u64,
+		_nonce: u64,
+	) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (account, ())))
+	}
 }
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
 	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
 }
+
+// Like `new_test_ext`, with the offchain APIs and [`AUTHORITY`] as the only local key. Returns the
+// pool the offchain worker submits transactions to.
+pub fn new_offchain_test_ext() -> (sp_io::TestExternalities, Arc<RwLock<PoolState>>) {
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	UintAuthorityId::set_all_keys([AUTHORITY]);
+
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	(ext, pool_state)
+}
@@ -0,0 +1,82 @@
+//! The types used by the offchain worker of the template pallet.
+//!
+//! On every block, the offchain worker reads a value from a [`ValueSource`] and, if it differs
+//! from [`crate::Something`], submits it back on chain:
+//! - on even blocks, with a signed transaction from an account of the [`KEY_TYPE`] keystore keys,
+//!   paying the fees;
+//! - on odd blocks, with an unsigned transaction carrying a [`ValuePayload`] signed by such a key.
+//!   It pays no fees, so it is rate-limited by [`crate::Config::UnsignedInterval`].
+//!
+//! The keys are added to the keystore of the node, e.g. with the `key insert` subcommand and
+//! `--key-type tmpl`.
+
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::traits::Get;
+use frame_system::offchain::{SignedPayload, SigningTypes};
+use scale_info::TypeInfo;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+
+/// The key type of the keys signing the transactions of the offchain worker.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// The sr25519 application crypto of [`KEY_TYPE`].
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// The identifier of the keys signing the transactions of the offchain worker, to set as
+	/// [`crate::Config::AuthorityId`].
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// A source of values for the offchain worker.
+pub trait ValueSource {
+	/// The value to submit, if any.
+	///
+	/// This is called from the offchain worker, so it can use the offchain APIs.
+	fn value() -> Option<u32>;
+}
+
+/// Reads the SCALE-encoded `u32` stored under `Key` in the persistent offchain storage of the
+/// node.
+///
+/// The value can be set with the `offchain_localStorageSet` RPC method.
+pub struct LocalStorageSource<Key>(PhantomData<Key>);
+
+impl<Key: Get<&'static [u8]>> ValueSource for LocalStorageSource<Key> {
+	fn value() -> Option<u32> {
+		StorageValueRef::persistent(Key::get()).get::<u32>().ok().flatten()
+	}
+}
+
+/// The payload of the unsigned transactions of the offchain worker, signed by `public`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub struct ValuePayload<Public, BlockNumber> {
+	/// The block the offchain worker ran at.
+	pub block_number: BlockNumber,
+	/// The value to store.
+	pub value: u32,
+	/// The key signing the payload.
+	pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T>
+	for ValuePayload<T::Public, frame_system::pallet_prelude::BlockNumberFor<T>>
+{
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
@@ -1,5 +1,21 @@
-use crate::{mock::*, Error, Event, Something};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations::v1, mock::*, offchain::ValuePayload, AccountSomething, Call, Error, Event, History,
+	NextUnsignedAt, Something, SomethingInfo,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+	unsigned::ValidateUnsigned,
+};
+use frame_system::offchain::SignedPayload;
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	testing::{TestSignature, UintAuthorityId},
+	traits::Dispatchable,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	BuildStorage,
+};
 
 #[test]
 fn it_works_for_default_value() {
@@ -9,9 +25,9 @@ fn it_works_for_default_value() {
 		// Dispatch a signed extrinsic.
 		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(Something::<Test>::get(), Some(42));
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 1 }));
 		// Assert that the correct event was deposited
-		System::assert_last_event(Event::SomethingStored { something: 42, who: 1 }.into());
+		System::assert_last_event(Event::SomethingStored { who: 1, old: None, new: 42 }.into());
 	});
 }
 
@@ -25,3 +41,236 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn values_are_stored_per_account() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 7));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 43));
+
+		// The global value is overwritten by every caller, the per-account ones are not.
+		assert_eq!(TemplateModule::something(), Some(43));
+		assert_eq!(AccountSomething::<Test>::get(1), Some(43));
+		assert_eq!(AccountSomething::<Test>::get(2), Some(7));
+		System::assert_last_event(Event::SomethingStored { who: 1, old: Some(42), new: 43 }.into());
+	});
+}
+
+#[test]
+fn history_keeps_the_latest_values() {
+	new_test_ext().execute_with(|| {
+		for value in 1..=5 {
+			assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), value));
+		}
+
+		// `MaxHistory` is 3 in the mock, so the two oldest values have been dropped.
+		assert_eq!(History::<Test>::get(1).into_inner(), vec![3, 4, 5]);
+		assert!(History::<Test>::get(2).is_empty());
+	});
+}
+
+#[test]
+fn clear_something_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 7));
+
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+
+		assert_eq!(AccountSomething::<Test>::get(1), None);
+		assert!(History::<Test>::get(1).is_empty());
+		// Other accounts and the global value are left untouched.
+		assert_eq!(AccountSomething::<Test>::get(2), Some(7));
+		assert_eq!(TemplateModule::something(), Some(7));
+		System::assert_last_event(Event::SomethingCleared { who: 1, old: 42 }.into());
+	});
+}
+
+#[test]
+fn clear_something_fails_without_value() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::clear_something(RuntimeOrigin::signed(1)),
+			Error::<Test>::NoneValue
+		);
+	});
+}
+
+#[test]
+fn migration_to_v1_moves_something() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		v1::v0::Something::<Test>::put(42);
+		System::set_block_number(5);
+
+		v1::MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 5 }));
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+
+		// Running the migration again is a no-op.
+		System::set_block_number(6);
+		v1::MigrateV0ToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 5 }));
+	});
+}
+
+#[test]
+fn migration_to_v1_handles_missing_value() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+
+		v1::MigrateV0ToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(Something::<Test>::get(), None);
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn genesis_config_sets_something() {
+	let genesis = RuntimeGenesisConfig {
+		template_module: crate::GenesisConfig { something: Some(42), ..Default::default() },
+		..Default::default()
+	};
+	sp_io::TestExternalities::from(genesis.build_storage().unwrap()).execute_with(|| {
+		assert_eq!(Something::<Test>::get(), Some(SomethingInfo { value: 42, updated_at: 0 }));
+	});
+
+	new_test_ext().execute_with(|| assert_eq!(Something::<Test>::get(), None));
+}
+
+/// The transactions submitted to the pool, oldest first.
+fn submitted(pool: &parking_lot::RwLock<sp_core::offchain::testing::PoolState>) -> Vec<Extrinsic> {
+	let transactions = std::mem::take(&mut pool.write().transactions);
+	transactions.iter().map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap()).collect()
+}
+
+#[test]
+fn offchain_worker_submits_signed_transactions_on_even_blocks() {
+	let (mut ext, pool) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		StorageValueRef::persistent(VALUE_KEY).set(&42u32);
+		System::set_block_number(2);
+
+		TemplateModule::offchain_worker(2);
+
+		let [tx] = &submitted(&pool)[..] else { panic!("one transaction was submitted") };
+		assert_eq!(tx.signature, Some((AUTHORITY, ())));
+		assert_eq!(tx.call, RuntimeCall::TemplateModule(Call::submit_value_signed { value: 42 }));
+
+		assert_ok!(tx.call.clone().dispatch(RuntimeOrigin::signed(AUTHORITY)));
+		assert_eq!(TemplateModule::something(), Some(42));
+		System::assert_last_event(Event::ValueSubmitted { who: Some(AUTHORITY), value: 42 }.into());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_transactions_with_signed_payloads_on_odd_blocks() {
+	let (mut ext, pool) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		StorageValueRef::persistent(VALUE_KEY).set(&42u32);
+		System::set_block_number(3);
+
+		TemplateModule::offchain_worker(3);
+
+		let [tx] = &submitted(&pool)[..] else { panic!("one transaction was submitted") };
+		assert_eq!(tx.signature, None);
+		let RuntimeCall::TemplateModule(call @ Call::submit_value_unsigned { payload, signature }) =
+			&tx.call
+		else {
+			panic!("unexpected call {:?}", tx.call)
+		};
+		let expected =
+			ValuePayload { block_number: 3, value: 42, public: UintAuthorityId(AUTHORITY) };
+		assert_eq!(payload, &expected);
+		assert!(SignedPayload::<Test>::verify::<TestAuthId>(payload, signature.clone()));
+
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::Local, call));
+		assert_ok!(tx.call.clone().dispatch(RuntimeOrigin::none()));
+		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(NextUnsignedAt::<Test>::get(), 3 + 5);
+		System::assert_last_event(Event::ValueSubmitted { who: None, value: 42 }.into());
+
+		// Only one unsigned transaction is accepted per interval.
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::Local, call),
+			InvalidTransaction::Stale.into()
+		);
+		StorageValueRef::persistent(VALUE_KEY).set(&43u32);
+		TemplateModule::offchain_worker(5);
+		assert!(submitted(&pool).is_empty());
+	});
+}
+
+#[test]
+fn offchain_worker_only_submits_new_values() {
+	let (mut ext, pool) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		// Nothing to submit without a value.
+		TemplateModule::offchain_worker(2);
+		assert!(submitted(&pool).is_empty());
+
+		StorageValueRef::persistent(VALUE_KEY).set(&42u32);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		TemplateModule::offchain_worker(2);
+		assert!(submitted(&pool).is_empty());
+	});
+}
+
+#[test]
+fn unsigned_transactions_need_a_valid_signature() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let payload =
+			ValuePayload { block_number: 3, value: 42, public: UintAuthorityId(AUTHORITY) };
+
+		// Signed by another key.
+		let signature = TestSignature(AUTHORITY + 1, payload.encode());
+		let call = Call::submit_value_unsigned { payload: payload.clone(), signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+
+		// From a future block.
+		let payload = ValuePayload { block_number: 4, ..payload };
+		let signature = TestSignature(AUTHORITY, payload.encode());
+		let call = Call::submit_value_unsigned { payload, signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Future.into()
+		);
+
+		// Unsigned transactions cannot be sent signed.
+		assert_noop!(
+			TemplateModule::submit_value_unsigned(
+				RuntimeOrigin::signed(AUTHORITY),
+				ValuePayload { block_number: 3, value: 42, public: UintAuthorityId(AUTHORITY) },
+				TestSignature(AUTHORITY, vec![])
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn unsigned_transactions_need_an_allowed_signer() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let outsider = AUTHORITY + 1;
+		let payload =
+			ValuePayload { block_number: 3, value: 42, public: UintAuthorityId(outsider) };
+		let signature = TestSignature(outsider, payload.encode());
+		assert!(SignedPayload::<Test>::verify::<TestAuthId>(&payload, signature.clone()));
+
+		let call = Call::submit_value_unsigned { payload, signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+	});
+}
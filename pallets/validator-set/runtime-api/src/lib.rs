@@ -0,0 +1,20 @@
+//! Runtime API definition for the validator set pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Access to the validator set and the validators disabled in the current session.
+	pub trait ValidatorSetApi<AccountId>
+	where
+		AccountId: Codec,
+	{
+		/// The validator set that will be handed to the session pallet at the next rotation.
+		fn validators() -> Vec<AccountId>;
+
+		/// The validators disabled for the rest of the current session.
+		fn disabled_validators() -> Vec<AccountId>;
+	}
+}
@@ -0,0 +1,64 @@
+//! Benchmarking setup for pallet-validator-set
+#![cfg(feature = "runtime-benchmarks")]
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ValidatorSet;
+use frame_benchmarking::v2::*;
+use frame_support::{traits::Get, BoundedVec};
+use frame_system::RawOrigin;
+
+/// Fill the validator set with `count` validators.
+fn set_validators<T: Config>(count: u32) {
+	let validators = (0..count).map(|i| account("validator", i, 0)).collect();
+	Validators::<T>::put(BoundedVec::<_, T::MaxValidators>::truncate_from(validators));
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn add_validator() -> Result<(), BenchmarkError> {
+		set_validators::<T>(T::MaxValidators::get() - 1);
+		let origin =
+			T::AddRemoveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let who: T::AccountId = whitelisted_caller();
+		#[extrinsic_call]
+		add_validator(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(Validators::<T>::get().contains(&who));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_validator() -> Result<(), BenchmarkError> {
+		set_validators::<T>(T::MaxValidators::get());
+		let origin =
+			T::AddRemoveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		// The last validator is the worst case for the lookup.
+		let who: T::AccountId = account("validator", T::MaxValidators::get() - 1, 0);
+		#[extrinsic_call]
+		remove_validator(origin as T::RuntimeOrigin, who.clone());
+
+		assert!(!Validators::<T>::get().contains(&who));
+		Ok(())
+	}
+
+	/// `v` is the number of validators of the session, of which the last one is disabled.
+	#[benchmark]
+	fn disable_validator(v: Linear<2, { T::MaxValidators::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::AddRemoveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let validators: Vec<T::AccountId> = (0..v).map(|i| account("validator", i, 0)).collect();
+		let who = validators[validators.len() - 1].clone();
+		T::Disabler::set_session_validators(validators);
+		#[extrinsic_call]
+		disable_validator(origin as T::RuntimeOrigin, who.clone());
+
+		assert_eq!(T::Disabler::disabled_set(), vec![who]);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(ValidatorSet, crate::mock::new_test_ext(), crate::mock::Test);
+}
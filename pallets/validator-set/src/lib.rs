@@ -0,0 +1,398 @@
+//! # Validator Set Pallet
+//!
+//! A pallet that maintains the set of validators of a permissioned chain and hands it to
+//! `pallet_session`, which in turn rotates the Aura and GRANDPA authorities at session
+//! boundaries.
+//!
+//! ## Overview
+//!
+//! The validator set is seeded from the chain spec and can afterwards be changed by
+//! [`Config::AddRemoveOrigin`] (typically root) through [`Pallet::add_validator`] and
+//! [`Pallet::remove_validator`]. Changes are picked up by `pallet_session` at the end of the
+//! current session and become active one session later, once the new validators' session keys
+//! have been queued.
+//!
+//! A validator only authors blocks and votes on finality once it has registered its session keys
+//! with `pallet_session::Pallet::set_keys`; validators without keys are skipped by the session
+//! pallet.
+//!
+//! To wire this pallet into a runtime, use it as the `SessionManager` of `pallet_session`,
+//! optionally wrapped in `pallet_session::historical::NoteHistoricalRoot` so that key-ownership
+//! proofs can be generated for past sessions.
+//!
+//! ## Offences
+//!
+//! The pallet implements [`OnOffenceHandler`] so that it can be used as the offence handler of
+//! `pallet_offences`. Reported offenders are removed from the validator set, unless that would
+//! leave fewer than [`Config::MinValidators`] validators.
+//!
+//! Depending on the offence's disable strategy, offenders are also disabled through
+//! [`Config::Disabler`] for the rest of the current session. [`Config::AddRemoveOrigin`] can do
+//! the same with [`Pallet::disable_validator`]. Using `pallet_session` as the disabler and as
+//! `pallet_aura::Config::DisabledValidators` makes blocks authored by disabled validators invalid,
+//! so their slots are skipped.
+
+// We make sure this pallet uses `no_std` for compiling to Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Re-export pallet items so that they can be accessed from the crate namespace.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::{
+	traits::{Convert, Zero},
+	Perbill,
+};
+use sp_staking::{
+	offence::{DisableStrategy, OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::prelude::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The pallet's configuration trait.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching runtime event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+		/// The origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// The minimum number of validators that must remain in the set.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+		/// The maximum number of validators in the set.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+		/// Disables validators for the rest of the current session.
+		type Disabler: DisableValidators<Self::AccountId>;
+		/// The number of past sessions whose roots `pallet_session::historical` keeps when this
+		/// pallet is its session manager. Older roots are pruned, so key ownership can no longer
+		/// be proven for those sessions.
+		#[pallet::constant]
+		type HistoricalSessions: Get<SessionIndex>;
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The current validator set, handed to `pallet_session` at the next session rotation.
+	#[pallet::storage]
+	pub type Validators<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxValidators>, ValueQuery>;
+
+	/// Whether [`Validators`] changed since it was last handed to `pallet_session`.
+	#[pallet::storage]
+	pub type SetChanged<T> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The validators of the first session.
+		pub initial_validators: Vec<T::AccountId>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			let mut validators = self.initial_validators.clone();
+			validators.sort();
+			validators.dedup();
+			assert_eq!(
+				validators.len(),
+				self.initial_validators.len(),
+				"Duplicate validators in genesis."
+			);
+			assert!(
+				validators.len() as u32 >= T::MinValidators::get(),
+				"Fewer than `MinValidators` validators in genesis."
+			);
+
+			let validators =
+				BoundedVec::<_, T::MaxValidators>::try_from(self.initial_validators.clone())
+					.expect("More than `MaxValidators` validators in genesis.");
+			Validators::<T>::put(validators);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was added to the set. It becomes active after the next session rotation.
+		ValidatorAdded {
+			/// The added validator.
+			who: T::AccountId,
+		},
+		/// A validator was removed from the set. It becomes inactive after the next session
+		/// rotation.
+		ValidatorRemoved {
+			/// The removed validator.
+			who: T::AccountId,
+		},
+		/// An offence committed by a validator was reported.
+		OffenceReported {
+			/// The offending validator.
+			who: T::AccountId,
+			/// The session in which the offence was committed.
+			session: SessionIndex,
+			/// The fraction of the offender's stake that would be slashed.
+			slash_fraction: Perbill,
+		},
+		/// A validator was disabled for the rest of the current session.
+		ValidatorDisabled {
+			/// The disabled validator.
+			who: T::AccountId,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a validator.
+		Duplicate,
+		/// The account is not a validator.
+		NotValidator,
+		/// The validator set is full.
+		TooManyValidators,
+		/// Removing the validator would leave fewer than [`Config::MinValidators`] validators.
+		TooFewValidators,
+		/// The account is not an enabled validator of the current session, or it is the last one.
+		CannotDisable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add `who` to the validator set.
+		///
+		/// The new validator starts authoring two sessions later, provided it has set its session
+		/// keys by then.
+		///
+		/// The dispatch origin must be [`Config::AddRemoveOrigin`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::add_validator())]
+		pub fn add_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			Validators::<T>::try_mutate(|validators| -> DispatchResult {
+				ensure!(!validators.contains(&who), Error::<T>::Duplicate);
+				validators.try_push(who.clone()).map_err(|_| Error::<T>::TooManyValidators)?;
+				Ok(())
+			})?;
+			SetChanged::<T>::put(true);
+
+			Self::deposit_event(Event::ValidatorAdded { who });
+			Ok(())
+		}
+
+		/// Remove `who` from the validator set.
+		///
+		/// The validator stops authoring two sessions later.
+		///
+		/// The dispatch origin must be [`Config::AddRemoveOrigin`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::remove_validator())]
+		pub fn remove_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			Self::do_remove_validator(&who)?;
+
+			Self::deposit_event(Event::ValidatorRemoved { who });
+			Ok(())
+		}
+
+		/// Disable `who` for the rest of the current session.
+		///
+		/// A disabled validator cannot author blocks. It stays in the validator set and is enabled
+		/// again at the next session rotation; use [`Pallet::remove_validator`] to drop it.
+		///
+		/// The dispatch origin must be [`Config::AddRemoveOrigin`]. The weight assumes a session
+		/// of [`Config::MaxValidators`] validators.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::disable_validator(T::MaxValidators::get()))]
+		pub fn disable_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			ensure!(T::Disabler::disable_validator(&who), Error::<T>::CannotDisable);
+
+			Self::deposit_event(Event::ValidatorDisabled { who });
+			Ok(())
+		}
+	}
+}
+
+/// Something that can disable validators of the current session.
+pub trait DisableValidators<AccountId> {
+	/// Disable `who` until the end of the current session.
+	///
+	/// Returns `false` if `who` is not a validator of the current session, is already disabled or
+	/// is the last enabled validator.
+	fn disable_validator(who: &AccountId) -> bool;
+
+	/// The validators disabled in the current session.
+	fn disabled_set() -> Vec<AccountId>;
+
+	/// Make `validators` the enabled validators of the current session.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_session_validators(validators: Vec<AccountId>);
+}
+
+/// Disables validators through `pallet_session`, which re-enables them at the next session.
+impl<T: pallet_session::Config> DisableValidators<T::ValidatorId> for pallet_session::Pallet<T> {
+	fn disable_validator(who: &T::ValidatorId) -> bool {
+		let enabled = Self::validators().len().saturating_sub(Self::disabled_validators().len());
+		// Keep at least one validator able to author blocks.
+		enabled > 1 && Self::disable(who)
+	}
+
+	fn disabled_set() -> Vec<T::ValidatorId> {
+		let validators = Self::validators();
+		Self::disabled_validators()
+			.into_iter()
+			.filter_map(|index| validators.get(index as usize).cloned())
+			.collect()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_session_validators(validators: Vec<T::ValidatorId>) {
+		pallet_session::Validators::<T>::put(validators);
+		pallet_session::DisabledValidators::<T>::kill();
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The validators disabled in the current session.
+	pub fn disabled_validators() -> Vec<T::AccountId> {
+		T::Disabler::disabled_set()
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Remove `who` from the validator set, keeping at least [`Config::MinValidators`].
+	fn do_remove_validator(who: &T::AccountId) -> Result<(), Error<T>> {
+		Validators::<T>::try_mutate(|validators| {
+			let index = validators.iter().position(|v| v == who).ok_or(Error::<T>::NotValidator)?;
+			if validators.len() as u32 <= T::MinValidators::get() {
+				return Err(Error::<T>::TooFewValidators)
+			}
+			validators.remove(index);
+			Ok(())
+		})?;
+		SetChanged::<T>::put(true);
+		Ok(())
+	}
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		// Only report a new set when it changed, so that the session pallet does not needlessly
+		// schedule authority changes.
+		SetChanged::<T>::take().then(|| Validators::<T>::get().into_inner())
+	}
+
+	fn new_session_genesis(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(Validators::<T>::get().into_inner())
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+
+	fn start_session(_start_index: SessionIndex) {}
+}
+
+/// Lets `pallet_session::historical::NoteHistoricalRoot` wrap this pallet, identifying each
+/// validator through `FullIdentificationOf`.
+impl<T> pallet_session::historical::SessionManager<T::AccountId, T::FullIdentification>
+	for Pallet<T>
+where
+	T: Config + pallet_session::historical::Config,
+	T: pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+{
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, T::FullIdentification)>> {
+		pallet_session::historical::Pallet::<T>::prune_up_to(
+			new_index.saturating_sub(T::HistoricalSessions::get()),
+		);
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(Self::with_full_identification)
+	}
+
+	fn new_session_genesis(
+		new_index: SessionIndex,
+	) -> Option<Vec<(T::AccountId, T::FullIdentification)>> {
+		<Self as pallet_session::SessionManager<_>>::new_session_genesis(new_index)
+			.map(Self::with_full_identification)
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+}
+
+impl<T> Pallet<T>
+where
+	T: Config + pallet_session::historical::Config,
+	T: pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+{
+	fn with_full_identification(
+		validators: Vec<T::AccountId>,
+	) -> Vec<(T::AccountId, T::FullIdentification)> {
+		validators
+			.into_iter()
+			.filter_map(|v| T::FullIdentificationOf::convert(v.clone()).map(|id| (v, id)))
+			.collect()
+	}
+}
+
+impl<T: Config, I> OnOffenceHandler<T::AccountId, (T::AccountId, I), Weight> for Pallet<T> {
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, (T::AccountId, I)>],
+		slash_fraction: &[Perbill],
+		session: SessionIndex,
+		disable_strategy: DisableStrategy,
+	) -> Weight {
+		for (details, slash_fraction) in offenders.iter().zip(slash_fraction) {
+			let (who, _) = &details.offender;
+			Self::deposit_event(Event::OffenceReported {
+				who: who.clone(),
+				session,
+				slash_fraction: *slash_fraction,
+			});
+
+			// There is no stake to slash on a permissioned chain, so offenders lose their seat
+			// instead. Offenders that were already removed are ignored.
+			if Self::do_remove_validator(who).is_ok() {
+				Self::deposit_event(Event::ValidatorRemoved { who: who.clone() });
+			}
+
+			let disable = match disable_strategy {
+				DisableStrategy::Never => false,
+				DisableStrategy::WhenSlashed => !slash_fraction.is_zero(),
+				DisableStrategy::Always => true,
+			};
+			if disable && T::Disabler::disable_validator(who) {
+				Self::deposit_event(Event::ValidatorDisabled { who: who.clone() });
+			}
+		}
+
+		let count = offenders.len() as u64;
+		T::DbWeight::get().reads_writes(count.saturating_mul(3), count.saturating_mul(3))
+	}
+}
@@ -0,0 +1,101 @@
+use crate as pallet_validator_set;
+use crate::DisableValidators;
+use frame_support::{
+	derive_impl, parameter_types,
+	traits::{ConstU16, ConstU32, ConstU64},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		ValidatorSet: pallet_validator_set,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig as frame_system::DefaultConfig)]
+impl frame_system::Config for Test {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = ConstU64<250>;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ConstU16<42>;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+	/// The validators of the current session.
+	pub static SessionValidators: Vec<u64> = vec![1, 2, 3];
+	/// The validators disabled in the current session.
+	pub static Disabled: Vec<u64> = vec![];
+}
+
+/// Mimics `pallet_session`: session validators can be disabled as long as one stays enabled.
+pub struct MockDisabler;
+
+impl DisableValidators<u64> for MockDisabler {
+	fn disable_validator(who: &u64) -> bool {
+		let enabled = SessionValidators::get().len() - Disabled::get().len();
+		if !SessionValidators::get().contains(who) || Disabled::get().contains(who) || enabled <= 1
+		{
+			return false
+		}
+		Disabled::mutate(|disabled| disabled.push(*who));
+		true
+	}
+
+	fn disabled_set() -> Vec<u64> {
+		Disabled::get()
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn set_session_validators(validators: Vec<u64>) {
+		SessionValidators::set(validators);
+		Disabled::set(vec![]);
+	}
+}
+
+impl pallet_validator_set::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AddRemoveOrigin = EnsureRoot<u64>;
+	type MinValidators = ConstU32<1>;
+	type MaxValidators = ConstU32<4>;
+	type Disabler = MockDisabler;
+	type HistoricalSessions = ConstU32<8>;
+	type WeightInfo = ();
+}
+
+// Build genesis storage with validators 1, 2 and 3.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_validator_set::GenesisConfig::<Test> { initial_validators: vec![1, 2, 3] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	t.into()
+}
@@ -0,0 +1,167 @@
+use crate::{mock::*, Error, Event, SetChanged, Validators};
+use frame_support::{assert_noop, assert_ok};
+use pallet_session::SessionManager;
+use sp_runtime::{DispatchError, Perbill};
+use sp_staking::offence::{DisableStrategy, OffenceDetails, OnOffenceHandler};
+
+#[test]
+fn genesis_validators_are_used_for_the_first_session() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::new_session_genesis(0), Some(vec![1, 2, 3]));
+		// Nothing changed since genesis.
+		assert_eq!(ValidatorSet::new_session(1), None);
+	});
+}
+
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+
+		assert_eq!(Validators::<Test>::get().into_inner(), vec![1, 2, 3, 4]);
+		System::assert_last_event(Event::ValidatorAdded { who: 4 }.into());
+
+		// The new set is handed to the session pallet exactly once.
+		assert_eq!(ValidatorSet::new_session(1), Some(vec![1, 2, 3, 4]));
+		assert!(!SetChanged::<Test>::get());
+		assert_eq!(ValidatorSet::new_session(2), None);
+	});
+}
+
+#[test]
+fn add_validator_checks_origin_and_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::signed(1), 4),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), 1),
+			Error::<Test>::Duplicate
+		);
+
+		assert_ok!(ValidatorSet::add_validator(RuntimeOrigin::root(), 4));
+		// `MaxValidators` is 4 in the mock.
+		assert_noop!(
+			ValidatorSet::add_validator(RuntimeOrigin::root(), 5),
+			Error::<Test>::TooManyValidators
+		);
+	});
+}
+
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 2));
+
+		assert_eq!(Validators::<Test>::get().into_inner(), vec![1, 3]);
+		System::assert_last_event(Event::ValidatorRemoved { who: 2 }.into());
+		assert_eq!(ValidatorSet::new_session(1), Some(vec![1, 3]));
+	});
+}
+
+#[test]
+fn remove_validator_keeps_minimum() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 4),
+			Error::<Test>::NotValidator
+		);
+
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 1));
+		assert_ok!(ValidatorSet::remove_validator(RuntimeOrigin::root(), 2));
+		// `MinValidators` is 1 in the mock.
+		assert_noop!(
+			ValidatorSet::remove_validator(RuntimeOrigin::root(), 3),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
+
+#[test]
+fn offenders_are_removed() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let offence = |who: u64| OffenceDetails { offender: (who, ()), reporters: vec![] };
+
+		ValidatorSet::on_offence(
+			&[offence(2), offence(3)],
+			&[Perbill::from_percent(10), Perbill::from_percent(10)],
+			5,
+			DisableStrategy::WhenSlashed,
+		);
+
+		System::assert_has_event(
+			Event::OffenceReported {
+				who: 2,
+				session: 5,
+				slash_fraction: Perbill::from_percent(10),
+			}
+			.into(),
+		);
+		System::assert_has_event(Event::ValidatorRemoved { who: 3 }.into());
+		System::assert_has_event(Event::ValidatorDisabled { who: 3 }.into());
+		assert_eq!(ValidatorSet::new_session(6), Some(vec![1]));
+		assert_eq!(ValidatorSet::disabled_validators(), vec![2, 3]);
+
+		// The last validator is kept and stays enabled, even if it misbehaves.
+		ValidatorSet::on_offence(&[offence(1)], &[Perbill::one()], 6, DisableStrategy::Always);
+		assert_eq!(Validators::<Test>::get().into_inner(), vec![1]);
+		assert_eq!(ValidatorSet::disabled_validators(), vec![2, 3]);
+	});
+}
+
+#[test]
+fn offenders_are_not_disabled_without_slash() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::on_offence(
+			&[OffenceDetails { offender: (2, ()), reporters: vec![] }],
+			&[Perbill::zero()],
+			5,
+			DisableStrategy::WhenSlashed,
+		);
+
+		assert!(ValidatorSet::disabled_validators().is_empty());
+	});
+}
+
+#[test]
+fn disable_validator_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			ValidatorSet::disable_validator(RuntimeOrigin::signed(1), 2),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(ValidatorSet::disable_validator(RuntimeOrigin::root(), 2));
+
+		System::assert_last_event(Event::ValidatorDisabled { who: 2 }.into());
+		assert_eq!(ValidatorSet::disabled_validators(), vec![2]);
+		// Disabled validators stay in the set.
+		assert_eq!(Validators::<Test>::get().into_inner(), vec![1, 2, 3]);
+	});
+}
+
+#[test]
+fn disable_validator_keeps_one_enabled() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::disable_validator(RuntimeOrigin::root(), 4),
+			Error::<Test>::CannotDisable
+		);
+
+		assert_ok!(ValidatorSet::disable_validator(RuntimeOrigin::root(), 1));
+		assert_noop!(
+			ValidatorSet::disable_validator(RuntimeOrigin::root(), 1),
+			Error::<Test>::CannotDisable
+		);
+		assert_ok!(ValidatorSet::disable_validator(RuntimeOrigin::root(), 2));
+		assert_noop!(
+			ValidatorSet::disable_validator(RuntimeOrigin::root(), 3),
+			Error::<Test>::CannotDisable
+		);
+	});
+}
@@ -0,0 +1,69 @@
+//! Runtime API definitions of the node template runtime, besides those of its pallets.
+//!
+//! The APIs are implemented by the runtime in `impl_runtime_apis!`, and used by the node and other
+//! tools without depending on the runtime itself.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::vec::Vec;
+
+/// The type of network a genesis preset is meant for, as the `ChainType` of chain specs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum PresetChainType {
+	/// A development chain, run by a single node.
+	Development,
+	/// A local testnet.
+	Local,
+	/// A long-lived network.
+	Live,
+}
+
+/// The chain a genesis preset is meant for, naming the chain specs built from it.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PresetChain {
+	/// The human-readable name of the chain.
+	pub name: Vec<u8>,
+	/// The identifier of the chain.
+	pub id: Vec<u8>,
+	/// The type of the chain.
+	pub chain_type: PresetChainType,
+}
+
+/// The vesting balances of an account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct VestingBalances<Balance> {
+	/// The part of the vesting schedules not vested yet.
+	pub locked: Balance,
+	/// The part of the vesting schedules vested so far, claimed or not. Schedules are dropped once
+	/// fully vested and claimed.
+	pub vested: Balance,
+	/// The vested part still held by the vesting lock, released by calling `Vesting::vest`.
+	pub claimable: Balance,
+}
+
+sp_api::decl_runtime_apis! {
+	/// Named genesis config presets, complementing `sp_genesis_builder::GenesisBuilder`.
+	pub trait GenesisPresetsApi {
+		/// The names of the available presets.
+		fn preset_names() -> Vec<Vec<u8>>;
+
+		/// The JSON patch of the preset called `name`, or `None` if there is no such preset.
+		fn get_preset(name: Vec<u8>) -> Option<Vec<u8>>;
+
+		/// The chain the preset called `name` is meant for, or `None` if there is no such preset.
+		fn preset_chain(name: Vec<u8>) -> Option<PresetChain>;
+	}
+
+	/// Vesting balances of accounts, complementing the raw storage of `pallet_vesting`.
+	pub trait VestingApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The vesting balances of `who` at the current block, or `None` if it has no vesting
+		/// schedule.
+		fn vesting_balances(who: AccountId) -> Option<VestingBalances<Balance>>;
+	}
+}
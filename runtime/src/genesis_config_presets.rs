@@ -0,0 +1,279 @@
+//! Named genesis config presets.
+//!
+//! A preset is a JSON patch over the default `RuntimeGenesisConfig`, ready to be passed to
+//! `GenesisBuilder_build_config`. The presets, and the chains they are meant for, are exposed
+//! through `node_template_runtime_api::GenesisPresetsApi`, so chain specs can be built from the
+//! Wasm blob alone.
+//!
+//! The runtime cannot derive keys from seeds, so the public keys of the well-known dev accounts
+//! (`//Alice`, `//Bob`, ...) are spelled out below.
+
+use crate::{
+	opaque::SessionKeys, AccountId, AuraId, Balance, BlockNumber, GrandpaId, TreasuryAccount, DAYS,
+	EXISTENTIAL_DEPOSIT,
+};
+use hex_literal::hex;
+use node_template_runtime_api::{PresetChain, PresetChainType};
+use sp_core::{ed25519, sr25519};
+use sp_std::prelude::*;
+
+/// Single validator and pre-funded dev accounts, for `--dev`.
+pub const DEVELOPMENT: &str = "development";
+/// Alice and Bob as validators and pre-funded dev accounts, for multi-node local networks.
+pub const LOCAL_TESTNET: &str = "local_testnet";
+/// Four validators and no other pre-funded account, to rehearse the launch of a network locally.
+///
+/// Its validators and sudo key are the well-known dev accounts, so it is a local chain: real
+/// networks are built from the keys of their operators, e.g. with the node's `generate-spec`.
+pub const STAGING: &str = "staging";
+
+/// A well-known dev account: its sr25519 account and Aura key, and its ed25519 GRANDPA key.
+struct DevAccount {
+	sr25519: [u8; 32],
+	ed25519: [u8; 32],
+}
+
+const ALICE: DevAccount = DevAccount {
+	sr25519: hex!("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"),
+	ed25519: hex!("88dc3417d5058ec4b4503e0c12ea1a0a89be200fe98922423d4334014fa6b0ee"),
+};
+const BOB: DevAccount = DevAccount {
+	sr25519: hex!("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"),
+	ed25519: hex!("d17c2d7823ebf260fd138f2d7e27d114c0145d968b5ff5006125f2414fadae69"),
+};
+const CHARLIE: DevAccount = DevAccount {
+	sr25519: hex!("90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22"),
+	ed25519: hex!("439660b36c6c03afafca027b910b4fecf99801834c62a5e6006f27d978de234f"),
+};
+const DAVE: DevAccount = DevAccount {
+	sr25519: hex!("306721211d5404bd9da88e0204360a1a9ab8b87c66c1bc2fcdd37f3c2222cc20"),
+	ed25519: hex!("5e639b43e0052c47447dac87d6fd2b6ec50bdd4d0f614e4299c665249bbd09d9"),
+};
+
+/// The sr25519 accounts of `//Eve` and `//Ferdie`.
+const EVE: [u8; 32] = hex!("e659a7a1628cdd93febc04a4e0646ea20e9f5f0ce097d9a05290d4a9e054df4e");
+const FERDIE: [u8; 32] = hex!("1cbd2d43530a44705ad088af313e18f80b53ef16b36177cd4b77b846f2a5f07c");
+
+/// The balance of every pre-funded account.
+const ENDOWMENT: Balance = 1 << 60;
+
+/// The sr25519 accounts of `//Alice//stash` to `//Ferdie//stash`, in the same order.
+const STASHES: [[u8; 32]; 6] = [
+	hex!("be5ddb1579b72e84524fc29e78609e3caf42e85aa118ebfe0b0ad404b5bdd25f"),
+	hex!("fe65717dad0447d715f660a0a58411de509b42e6efb8375f562f58a554d5860e"),
+	hex!("1e07379407fecc4b89eb7dbd287c2c781cfb1907a96947a3eb18e4f8e7198625"),
+	hex!("e860f1b1c7227f7c22602f53f15af80747814dffd839719731ee3bba6edc126c"),
+	hex!("8ac59e11963af19174d0b94d5d78041c233f55d2e19324665bafdfb62925af2d"),
+	hex!("101191192fc877c24d725b337120fa3edc63d227bbc92705db1e2cb65f56981a"),
+];
+
+impl DevAccount {
+	fn account(&self) -> AccountId {
+		self.sr25519.into()
+	}
+
+	fn authority_keys(&self) -> (AccountId, AuraId, GrandpaId) {
+		(
+			self.account(),
+			sr25519::Public::from_raw(self.sr25519).into(),
+			ed25519::Public::from_raw(self.ed25519).into(),
+		)
+	}
+}
+
+/// The names of all presets, as accepted by [`get_preset`].
+pub fn preset_names() -> Vec<&'static str> {
+	vec![DEVELOPMENT, LOCAL_TESTNET, STAGING]
+}
+
+/// The JSON patch of the preset called `name`, or `None` if there is no such preset.
+pub fn get_preset(name: &str) -> Option<Vec<u8>> {
+	let patch = match name {
+		DEVELOPMENT => development_genesis(),
+		LOCAL_TESTNET => local_testnet_genesis(),
+		STAGING => staging_genesis(),
+		_ => return None,
+	};
+	Some(serde_json::to_vec(&patch).expect("serialization to json is expected to work. qed."))
+}
+
+/// The chain the preset called `name` is meant for, or `None` if there is no such preset.
+pub fn preset_chain(name: &str) -> Option<PresetChain> {
+	let (chain_name, id, chain_type) = match name {
+		DEVELOPMENT => ("Development", "dev", PresetChainType::Development),
+		LOCAL_TESTNET => ("Local Testnet", "local_testnet", PresetChainType::Local),
+		// The dev keys let anyone finalize and sudo the chain, so it must not be a live one.
+		STAGING => ("Staging Testnet", "staging_testnet", PresetChainType::Local),
+		_ => return None,
+	};
+	Some(PresetChain { name: chain_name.into(), id: id.into(), chain_type })
+}
+
+fn development_genesis() -> serde_json::Value {
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![ALICE.authority_keys()],
+		// Sudo account
+		ALICE.account(),
+		// Pre-funded accounts
+		vec![ALICE.account(), BOB.account(), STASHES[0].into(), STASHES[1].into()],
+		// Council
+		vec![ALICE.account(), BOB.account()],
+		// Technical committee
+		vec![ALICE.account()],
+		// Vesting schedules
+		vec![],
+	)
+}
+
+fn local_testnet_genesis() -> serde_json::Value {
+	let mut endowed_accounts = vec![
+		ALICE.account(),
+		BOB.account(),
+		CHARLIE.account(),
+		DAVE.account(),
+		EVE.into(),
+		FERDIE.into(),
+	];
+	endowed_accounts.extend(STASHES.iter().cloned().map(AccountId::from));
+
+	testnet_genesis(
+		// Initial PoA authorities
+		vec![ALICE.authority_keys(), BOB.authority_keys()],
+		// Sudo account
+		ALICE.account(),
+		// Pre-funded accounts
+		endowed_accounts,
+		// Council
+		vec![ALICE.account(), BOB.account(), CHARLIE.account()],
+		// Technical committee
+		vec![ALICE.account(), BOB.account()],
+		// Vesting schedules: Eve and Ferdie get a quarter of their balance at once, the rest over
+		// a year.
+		[EVE, FERDIE].map(|who| (who.into(), 0, 365 * DAYS, ENDOWMENT / 4)).to_vec(),
+	)
+}
+
+fn staging_genesis() -> serde_json::Value {
+	let authorities = [&ALICE, &BOB, &CHARLIE, &DAVE];
+
+	testnet_genesis(
+		// Initial PoA authorities
+		authorities.iter().map(|a| a.authority_keys()).collect(),
+		// Sudo account
+		ALICE.account(),
+		// Pre-funded accounts: only the validators, to pay for their session key updates.
+		authorities.iter().map(|a| a.account()).collect(),
+		// Council
+		authorities.iter().map(|a| a.account()).collect(),
+		// Technical committee
+		vec![ALICE.account(), BOB.account()],
+		// Vesting schedules
+		vec![],
+	)
+}
+
+/// Configure initial storage state for FRAME modules.
+///
+/// The Aura and GRANDPA authorities are not set directly: `pallet_session` derives them from the
+/// initial validators' session keys.
+///
+/// Vesting schedules are `(account, begin, length, liquid)`: the endowment of `account` minus
+/// `liquid` is locked, then vests linearly over `length` blocks from block `begin`.
+fn testnet_genesis(
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
+	root_key: AccountId,
+	endowed_accounts: Vec<AccountId>,
+	council: Vec<AccountId>,
+	technical_committee: Vec<AccountId>,
+	vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance)>,
+) -> serde_json::Value {
+	serde_json::json!({
+		"balances": {
+			// Configure endowed accounts with initial balance of 1 << 60, and the treasury with the
+			// existential deposit, so that fee shares below it are not burnt.
+			"balances": endowed_accounts
+				.iter()
+				.cloned()
+				.map(|k| (k, ENDOWMENT))
+				.chain([(TreasuryAccount::get(), EXISTENTIAL_DEPOSIT)])
+				.collect::<Vec<_>>(),
+		},
+		"validatorSet": {
+			"initialValidators": initial_authorities.iter().map(|x| x.0.clone()).collect::<Vec<_>>(),
+		},
+		"session": {
+			"keys": initial_authorities
+				.iter()
+				.map(|x| {
+					let keys = SessionKeys { aura: x.1.clone(), grandpa: x.2.clone() };
+					(x.0.clone(), x.0.clone(), keys)
+				})
+				.collect::<Vec<_>>(),
+		},
+		"council": {
+			"members": council,
+		},
+		"technicalCommittee": {
+			"members": technical_committee,
+		},
+		"vesting": {
+			"vesting": vesting,
+		},
+		"sudo": {
+			// Assign network admin rights until governance retires the key.
+			"key": Some(root_key),
+		},
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Pair;
+	use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+	#[test]
+	fn dev_keys_match_keyring() {
+		for (dev, sr, ed) in [
+			(&ALICE, Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+			(&BOB, Sr25519Keyring::Bob, Ed25519Keyring::Bob),
+			(&CHARLIE, Sr25519Keyring::Charlie, Ed25519Keyring::Charlie),
+			(&DAVE, Sr25519Keyring::Dave, Ed25519Keyring::Dave),
+		] {
+			assert_eq!(dev.sr25519, sr.public().0);
+			assert_eq!(dev.ed25519, ed.public().0);
+		}
+
+		assert_eq!(EVE, Sr25519Keyring::Eve.public().0);
+		assert_eq!(FERDIE, Sr25519Keyring::Ferdie.public().0);
+		for (stash, name) in
+			STASHES.iter().zip(["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"])
+		{
+			let pair = sr25519::Pair::from_string(&format!("//{name}//stash"), None).unwrap();
+			assert_eq!(*stash, pair.public().0);
+		}
+	}
+
+	#[test]
+	fn presets_are_valid_json() {
+		for name in preset_names() {
+			let patch = get_preset(name).expect("listed presets exist");
+			assert!(serde_json::from_slice::<serde_json::Value>(&patch).unwrap().is_object());
+			assert!(preset_chain(name).is_some());
+		}
+		assert!(get_preset("unknown").is_none());
+		assert!(preset_chain("unknown").is_none());
+	}
+
+	#[test]
+	fn presets_endow_the_treasury() {
+		let treasury = serde_json::json!([TreasuryAccount::get(), EXISTENTIAL_DEPOSIT]);
+		for name in preset_names() {
+			let patch: serde_json::Value =
+				serde_json::from_slice(&get_preset(name).unwrap()).unwrap();
+			let balances = patch["balances"]["balances"].as_array().unwrap();
+			assert!(balances.contains(&treasury), "{name} does not endow the treasury");
+		}
+	}
+}
@@ -3,15 +3,24 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use pallet_grandpa::AuthorityId as GrandpaId;
+use pallet_session::historical as pallet_session_historical;
+use smallvec::smallvec;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, One, Verify},
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	traits::{
+		AccountIdConversion, BlakeTwo256, Block as BlockT, Bounded, ConvertInto, DispatchInfoOf,
+		IdentifyAccount, NumberFor, OpaqueKeys, PostDispatchInfoOf, Replace, Verify,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+		TransactionValidityError,
+	},
+	ApplyExtrinsicResult, MultiSignature, RuntimeDebug,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -22,28 +31,35 @@ use frame_support::genesis_builder_helper::{build_config, create_default_config}
 pub use frame_support::{
 	construct_runtime, derive_impl, parameter_types,
 	traits::{
-		ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, KeyOwnerProofSystem, Randomness,
-		StorageInfo,
+		fungible::HoldConsideration, fungibles, tokens::ConversionToAssetBalance,
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains,
+		EitherOf, EitherOfDiverse, EqualPrivilegeOnly, InstanceFilter, KeyOwnerProofSystem,
+		LinearStoragePrice, MapSuccess, Randomness, StorageInfo, WithdrawReasons,
 	},
 	weights::{
 		constants::{
 			BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND,
 		},
-		IdentityFee, Weight,
+		ConstantMultiplier, Weight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
 	},
-	StorageValue,
+	PalletId, StorageValue,
 };
 pub use frame_system::Call as SystemCall;
+use frame_system::{EnsureRoot, EnsureRootWithSuccess, EnsureSigned};
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
-use pallet_transaction_payment::{ConstFeeMultiplier, CurrencyAdapter, Multiplier};
+use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{FixedPointNumber, Perbill, Permill, Perquintill};
 
 /// Import the template pallet.
 pub use pallet_template;
 
+pub mod genesis_config_presets;
+pub mod vesting_api;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -57,6 +73,9 @@ pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::Account
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of an asset of `pallet_assets`.
+pub type AssetId = u32;
+
 /// Index of a transaction in the chain.
 pub type Nonce = u32;
 
@@ -173,11 +192,14 @@ impl frame_system::Config for Runtime {
 	/// This is used as an identifier of the chain. 42 is the generic substrate prefix.
 	type SS58Prefix = SS58Prefix;
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	/// Rejects retired sudo calls, calls outside the safe mode whitelist and paused calls.
+	type BaseCallFilter = RuntimeCallFilter;
 }
 
 impl pallet_aura::Config for Runtime {
 	type AuthorityId = AuraId;
-	type DisabledValidators = ();
+	/// Blocks authored by validators disabled in the current session are rejected.
+	type DisabledValidators = Session;
 	type MaxAuthorities = ConstU32<32>;
 	type AllowMultipleBlocksPerSlot = ConstBool<false>;
 
@@ -185,16 +207,24 @@ impl pallet_aura::Config for Runtime {
 	type SlotDuration = pallet_aura::MinimumPeriodTimesTwo<Runtime>;
 }
 
+parameter_types! {
+	/// The number of past sessions for which GRANDPA equivocations can still be reported.
+	pub const MaxSetIdSessionEntries: u64 = 168;
+	/// How long (in blocks) an equivocation report stays valid in the transaction pool.
+	pub const ReportLongevity: u64 = MaxSetIdSessionEntries::get() * SessionPeriod::get() as u64;
+}
+
 impl pallet_grandpa::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 
 	type WeightInfo = ();
 	type MaxAuthorities = ConstU32<32>;
 	type MaxNominators = ConstU32<0>;
-	type MaxSetIdSessionEntries = ConstU64<0>;
+	type MaxSetIdSessionEntries = MaxSetIdSessionEntries;
 
-	type KeyOwnerProof = sp_core::Void;
-	type EquivocationReportSystem = ();
+	type KeyOwnerProof = <Historical as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
+	type EquivocationReportSystem =
+		pallet_grandpa::EquivocationReportSystem<Self, Offences, Historical, ReportLongevity>;
 }
 
 impl pallet_timestamp::Config for Runtime {
@@ -205,8 +235,18 @@ impl pallet_timestamp::Config for Runtime {
 	type WeightInfo = ();
 }
 
+// Units of the native currency, which has 12 decimals.
+pub const UNIT: Balance = 1_000_000_000_000;
+pub const MILLIUNIT: Balance = 1_000_000_000;
+pub const MICROUNIT: Balance = 1_000_000;
+
 /// Existential deposit.
-pub const EXISTENTIAL_DEPOSIT: u128 = 500;
+pub const EXISTENTIAL_DEPOSIT: Balance = MILLIUNIT;
+
+/// The deposit reserved for storing `items` entries taking `bytes` bytes in total.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	items as Balance * UNIT + (bytes as Balance) * 10 * MILLIUNIT
+}
 
 impl pallet_balances::Config for Runtime {
 	type MaxLocks = ConstU32<50>;
@@ -222,21 +262,60 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 	type FreezeIdentifier = ();
 	type MaxFreezes = ();
-	type RuntimeHoldReason = ();
+	type RuntimeHoldReason = RuntimeHoldReason;
 	type RuntimeFreezeReason = ();
 }
 
+/// Converts a weight into a fee.
+///
+/// The polynomial is calibrated so that an extrinsic of `ExtrinsicBaseWeight` costs
+/// `MILLIUNIT / 10` before the fee multiplier is applied, and scales linearly with weight beyond
+/// that.
+pub struct WeightToFee;
+
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		let p = MILLIUNIT / 10;
+		let q = Balance::from(ExtrinsicBaseWeight::get().ref_time());
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			negative: false,
+			coeff_frac: Perbill::from_rational(p % q, q),
+			coeff_integer: p / q,
+		}]
+	}
+}
+
 parameter_types! {
-	pub FeeMultiplier: Multiplier = Multiplier::one();
+	pub const TransactionByteFee: Balance = 10 * MICROUNIT;
+	/// The share of the normal dispatch class that blocks should be filled to. Fees rise while
+	/// blocks are fuller than this and fall while they are emptier.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// How quickly the fee multiplier reacts to deviations from `TargetBlockFullness`.
+	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(75, 1_000_000);
+	/// The fee multiplier never drops below this value, so fees never become negligible.
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 10u128);
+	pub MaximumMultiplier: Multiplier = Bounded::max_value();
 }
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction =
+		CurrencyAdapter<Balances, pallet_fee_distribution::DealWithFees<Self>>;
 	type OperationalFeeMultiplier = ConstU8<5>;
-	type WeightToFee = IdentityFee<Balance>;
-	type LengthToFee = IdentityFee<Balance>;
-	type FeeMultiplierUpdate = ConstFeeMultiplier<FeeMultiplier>;
+	type WeightToFee = WeightToFee;
+	type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
+	/// The multiplier is stored in `NextFeeMultiplier` and applied by `query_info` and
+	/// `query_fee_details`, so fee estimates follow congestion.
+	type FeeMultiplierUpdate = TargetedFeeAdjustment<
+		Self,
+		TargetBlockFullness,
+		AdjustmentVariable,
+		MinimumMultiplier,
+		MaximumMultiplier,
+	>;
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -245,10 +324,603 @@ impl pallet_sudo::Config for Runtime {
 	type WeightInfo = pallet_sudo::weights::SubstrateWeight<Runtime>;
 }
 
+impl pallet_utility::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+	pub const DepositBase: Balance = deposit(1, 88);
+	// Additional storage item size of 32 bytes.
+	pub const DepositFactor: Balance = deposit(0, 32);
+}
+
+impl pallet_multisig::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type DepositBase = DepositBase;
+	type DepositFactor = DepositFactor;
+	type MaxSignatories = ConstU32<100>;
+	type WeightInfo = pallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// One storage item; key size 32, value size 8.
+	pub const ProxyDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 33 bytes.
+	pub const ProxyDepositFactor: Balance = deposit(0, 33);
+	pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+	// Additional storage item size of 68 bytes: account, call hash and block number.
+	pub const AnnouncementDepositFactor: Balance = deposit(0, 68);
+}
+
+/// The type used to represent the kinds of proxying allowed.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	Encode,
+	Decode,
+	RuntimeDebug,
+	MaxEncodedLen,
+	scale_info::TypeInfo,
+)]
+pub enum ProxyType {
+	/// Any call.
+	Any,
+	/// Any call that cannot move funds out of the account.
+	NonTransfer,
+	/// Calls of the governance pallets.
+	Governance,
+	/// Calls of the template pallet.
+	TemplateOnly,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self {
+		Self::Any
+	}
+}
+
+impl InstanceFilter<RuntimeCall> for ProxyType {
+	fn filter(&self, c: &RuntimeCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			ProxyType::NonTransfer => !matches!(
+				c,
+				RuntimeCall::Balances(..) |
+					RuntimeCall::Assets(..) |
+					RuntimeCall::Vesting(pallet_vesting::Call::vested_transfer { .. })
+			),
+			ProxyType::Governance => matches!(
+				c,
+				RuntimeCall::Sudo(..) |
+					RuntimeCall::Council(..) |
+					RuntimeCall::TechnicalCommittee(..) |
+					RuntimeCall::Preimage(..) |
+					RuntimeCall::Scheduler(..) |
+					RuntimeCall::SafeMode(..) |
+					RuntimeCall::TxPause(..) |
+					RuntimeCall::Utility(..)
+			),
+			ProxyType::TemplateOnly =>
+				matches!(c, RuntimeCall::TemplateModule(..) | RuntimeCall::Utility(..)),
+		}
+	}
+
+	fn is_superset(&self, o: &Self) -> bool {
+		match (self, o) {
+			(x, y) if x == y => true,
+			(ProxyType::Any, _) => true,
+			(_, ProxyType::Any) => false,
+			(ProxyType::NonTransfer, _) => true,
+			_ => false,
+		}
+	}
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = ConstU32<32>;
+	type WeightInfo = pallet_proxy::weights::SubstrateWeight<Runtime>;
+	type MaxPending = ConstU32<32>;
+	type CallHasher = BlakeTwo256;
+	type AnnouncementDepositBase = AnnouncementDepositBase;
+	type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+	/// The number of blocks per session. Validator set changes are picked up at session
+	/// boundaries.
+	pub const SessionPeriod: BlockNumber = HOURS;
+	pub const SessionOffset: BlockNumber = 0;
+}
+
+impl pallet_session::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = AccountId;
+	type ValidatorIdOf = ConvertInto;
+	type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	type SessionManager = pallet_session_historical::NoteHistoricalRoot<Self, ValidatorSet>;
+	/// Aura and GRANDPA receive the authorities of each new session.
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type WeightInfo = pallet_session::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_session_historical::Config for Runtime {
+	/// Validators are identified by their account in key-ownership proofs.
+	type FullIdentification = AccountId;
+	type FullIdentificationOf = ConvertInto;
+}
+
+impl pallet_offences::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type IdentificationTuple = pallet_session_historical::IdentificationTuple<Self>;
+	/// Reported offenders are removed from the validator set.
+	type OnOffenceHandler = ValidatorSet;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type OverarchingCall = RuntimeCall;
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<(
+		RuntimeCall,
+		<UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload,
+	)> {
+		use sp_runtime::SaturatedConversion;
+
+		// The longest mortality allowed by `BlockHashCount`, from the parent block.
+		let period = BlockHashCount::get().checked_next_power_of_two().map_or(2, |c| c / 2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(0, None),
+		);
+		let payload = SignedPayload::new(call, extra).ok()?;
+		let signature = payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = payload.deconstruct();
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature, extra)))
+	}
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+	pub const TechnicalMotionDuration: BlockNumber = DAYS;
+	pub MaxProposalWeight: Weight = Perbill::from_percent(50) * BlockWeights::get().max_block;
+}
+
+type CouncilCollective = pallet_collective::Instance1;
+impl pallet_collective::Config<CouncilCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = CouncilMotionDuration;
+	type MaxProposals = ConstU32<100>;
+	type MaxMembers = ConstU32<100>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	type SetMembersOrigin = EnsureRootOrGovernance;
+	type MaxProposalWeight = MaxProposalWeight;
+}
+
+type TechnicalCollective = pallet_collective::Instance2;
+impl pallet_collective::Config<TechnicalCollective> for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
+	type Proposal = RuntimeCall;
+	type RuntimeEvent = RuntimeEvent;
+	type MotionDuration = TechnicalMotionDuration;
+	type MaxProposals = ConstU32<100>;
+	type MaxMembers = ConstU32<100>;
+	type DefaultVote = pallet_collective::PrimeDefaultVote;
+	type WeightInfo = pallet_collective::weights::SubstrateWeight<Runtime>;
+	type SetMembersOrigin = EnsureRootOrGovernance;
+	type MaxProposalWeight = MaxProposalWeight;
+}
+
+/// Root, or a motion approved by at least two thirds of the council.
+pub type EnsureRootOrTwoThirdsCouncil = EitherOfDiverse<
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+>;
+
+/// The origin of root-level actions: root, two thirds of the council or, in emergencies, the
+/// whole technical committee.
+pub type EnsureRootOrGovernance = EitherOfDiverse<
+	EnsureRootOrTwoThirdsCouncil,
+	pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 1>,
+>;
+
+/// [`EnsureRootOrGovernance`], yielding `D` on success.
+pub type EnsureRootOrGovernanceWithSuccess<D> = EitherOf<
+	EnsureRootWithSuccess<AccountId, D>,
+	EitherOf<
+		MapSuccess<
+			pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+			Replace<D>,
+		>,
+		MapSuccess<
+			pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 1, 1>,
+			Replace<D>,
+		>,
+	>,
+>;
+
+/// Configure the pallet-governance in pallets/governance.
+impl pallet_governance::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type RootOrigin = EnsureRootOrGovernance;
+	type Preimages = Preimage;
+	type WeightInfo = pallet_governance::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+	pub const PreimageHoldReason: RuntimeHoldReason =
+		RuntimeHoldReason::Preimage(pallet_preimage::HoldReason::Preimage);
+}
+
+/// Preimages are at most `pallet_preimage::MAX_SIZE` (4 MiB) long, but are noted by normal
+/// transactions, which cannot exceed 75% of the 5 MiB `BlockLength`, i.e. 3.75 MiB.
+impl pallet_preimage::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRootOrGovernance;
+	type Consideration = HoldConsideration<
+		AccountId,
+		Balances,
+		PreimageHoldReason,
+		LinearStoragePrice<PreimageBaseDeposit, PreimageByteDeposit, Balance>,
+	>;
+}
+
+parameter_types! {
+	/// Scheduled calls may use up to 80% of a block, leaving room for regular transactions.
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type PalletsOrigin = OriginCaller;
+	type RuntimeCall = RuntimeCall;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRootOrGovernance;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+/// The calls still allowed in safe mode: block production, governance and the circuit breakers.
+pub struct SafeModeWhitelistedCalls;
+impl Contains<RuntimeCall> for SafeModeWhitelistedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::System(..) |
+				RuntimeCall::Timestamp(..) |
+				RuntimeCall::Grandpa(..) |
+				RuntimeCall::Sudo(..) |
+				RuntimeCall::Council(..) |
+				RuntimeCall::TechnicalCommittee(..) |
+				RuntimeCall::Governance(..) |
+				RuntimeCall::SafeMode(..) |
+				RuntimeCall::TxPause(..)
+		)
+	}
+}
+
+parameter_types! {
+	/// How long safe mode lasts when entered or extended, unless exited earlier.
+	pub const SafeModeDuration: BlockNumber = DAYS;
+}
+
+impl pallet_safe_mode::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type WhitelistedCalls = SafeModeWhitelistedCalls;
+	type EnterDuration = SafeModeDuration;
+	type ExtendDuration = SafeModeDuration;
+	// Only governance can enter or extend safe mode: there is no permissionless deposit.
+	type EnterDepositAmount = ();
+	type ExtendDepositAmount = ();
+	type ForceEnterOrigin = EnsureRootOrGovernanceWithSuccess<SafeModeDuration>;
+	type ForceExtendOrigin = EnsureRootOrGovernanceWithSuccess<SafeModeDuration>;
+	type ForceExitOrigin = EnsureRootOrGovernance;
+	type ForceDepositOrigin = EnsureRootOrGovernance;
+	type ReleaseDelay = ();
+	type Notify = ();
+	type WeightInfo = pallet_safe_mode::weights::SubstrateWeight<Runtime>;
+}
+
+/// Pausing every call of a pallet is done by pausing this call name, e.g. `("Balances", "*")`.
+pub const PAUSE_WHOLE_PALLET: &[u8] = b"*";
+
+/// The pallets whose calls cannot be paused, so that governance can always lift a pause.
+pub struct TxPauseWhitelistedCalls;
+impl Contains<pallet_tx_pause::RuntimeCallNameOf<Runtime>> for TxPauseWhitelistedCalls {
+	fn contains((pallet, _): &pallet_tx_pause::RuntimeCallNameOf<Runtime>) -> bool {
+		matches!(
+			pallet.as_slice(),
+			b"System" |
+				b"Timestamp" | b"Sudo" |
+				b"Council" | b"TechnicalCommittee" |
+				b"Governance" |
+				b"SafeMode"
+		)
+	}
+}
+
+impl pallet_tx_pause::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PauseOrigin = EnsureRootOrGovernance;
+	type UnpauseOrigin = EnsureRootOrGovernance;
+	type WhitelistedCalls = TxPauseWhitelistedCalls;
+	type MaxNameLen = ConstU32<256>;
+	type WeightInfo = pallet_tx_pause::weights::SubstrateWeight<Runtime>;
+}
+
+/// The runtime's `BaseCallFilter`, also applied when validating transactions so that rejected
+/// calls never enter the pool. A call is allowed unless:
+/// - it is a call of `pallet_sudo` and the sudo key was retired;
+/// - safe mode is entered and the call is not in [`SafeModeWhitelistedCalls`];
+/// - the call, or its whole pallet through [`PAUSE_WHOLE_PALLET`], is paused.
+pub struct RuntimeCallFilter;
+impl Contains<RuntimeCall> for RuntimeCallFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		use frame_support::traits::GetCallMetadata;
+
+		if !pallet_governance::RetiredSudoFilter::<Runtime>::contains(call) ||
+			!SafeMode::contains(call) ||
+			!TxPause::contains(call)
+		{
+			return false
+		}
+		let pallet = call.get_call_metadata().pallet_name.as_bytes().to_vec();
+		!TxPause::is_paused_unbound(pallet, PAUSE_WHOLE_PALLET.to_vec())
+	}
+}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 10 * UNIT;
+	pub const AssetAccountDeposit: Balance = deposit(1, 16);
+	pub const MetadataDepositBase: Balance = deposit(1, 68);
+	pub const MetadataDepositPerByte: Balance = deposit(0, 1);
+	pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+}
+
+impl pallet_assets::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = EnsureRootOrGovernance;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	type RemoveItemsLimit = ConstU32<1000>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+/// Rates of the assets fees can be paid in: an asset without a rate cannot pay fees.
+impl pallet_asset_rate::Config for Runtime {
+	type CreateOrigin = EnsureRootOrGovernance;
+	type RemoveOrigin = EnsureRootOrGovernance;
+	type UpdateOrigin = EnsureRootOrGovernance;
+	type Currency = Balances;
+	type AssetKind = AssetId;
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_asset_rate::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+/// Converts native fees into asset fees with the rates of `pallet_asset_rate`, which give the
+/// value of one unit of an asset in native units.
+pub struct AssetRateConverter;
+impl ConversionToAssetBalance<Balance, AssetId, Balance> for AssetRateConverter {
+	type Error = ();
+
+	fn to_asset_balance(balance: Balance, asset_id: AssetId) -> Result<Balance, Self::Error> {
+		let rate = pallet_asset_rate::ConversionRateToNative::<Runtime>::get(asset_id).ok_or(())?;
+		Ok(rate.reciprocal().ok_or(())?.saturating_mul_int(balance))
+	}
+}
+
+/// Charges fees in assets like `pallet_asset_tx_payment::FungiblesAdapter`, but distributes them
+/// between the block author and the treasury with `pallet_fee_distribution`, like native fees.
+pub struct AssetFeesAdapter;
+impl pallet_asset_tx_payment::OnChargeAssetTransaction<Runtime> for AssetFeesAdapter {
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type LiquidityInfo = fungibles::Credit<AccountId, Assets>;
+
+	fn withdraw_fee(
+		who: &AccountId,
+		call: &RuntimeCall,
+		dispatch_info: &DispatchInfoOf<RuntimeCall>,
+		asset_id: AssetId,
+		fee: Balance,
+		tip: Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		<pallet_asset_tx_payment::FungiblesAdapter<AssetRateConverter, ()> as
+			pallet_asset_tx_payment::OnChargeAssetTransaction<Runtime>>::withdraw_fee(
+			who,
+			call,
+			dispatch_info,
+			asset_id,
+			fee,
+			tip,
+		)
+	}
+
+	fn correct_and_deposit_fee(
+		who: &AccountId,
+		_dispatch_info: &DispatchInfoOf<RuntimeCall>,
+		_post_info: &PostDispatchInfoOf<RuntimeCall>,
+		corrected_fee: Balance,
+		tip: Balance,
+		paid: Self::LiquidityInfo,
+	) -> Result<(Balance, Balance), TransactionValidityError> {
+		let asset = paid.asset();
+		let to_asset = |balance| {
+			AssetRateConverter::to_asset_balance(balance, asset)
+				.map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))
+		};
+		// `corrected_fee` includes the tip, as in `FungiblesAdapter`.
+		let min_fee = if corrected_fee == 0 { 0 } else { 1 };
+		let converted_fee = to_asset(corrected_fee)?.max(min_fee);
+		let converted_tip = to_asset(tip)?.min(converted_fee);
+
+		let (final_fee, refund) = paid.split(converted_fee);
+		let _ = <Assets as fungibles::Balanced<AccountId>>::resolve(who, refund);
+		let (tip, fee) = final_fee.split(converted_tip);
+		FeeDistribution::distribute_asset_fees(fee, tip);
+
+		Ok((converted_fee, converted_tip))
+	}
+}
+
+parameter_types! {
+	pub const MinVestedTransfer: Balance = UNIT;
+	/// Locked funds can still pay fees, but not be transferred or reserved.
+	pub UnvestedFundsAllowedWithdrawReasons: WithdrawReasons =
+		WithdrawReasons::except(WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE);
+}
+
+impl pallet_vesting::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+	type WeightInfo = pallet_vesting::weights::SubstrateWeight<Runtime>;
+	type UnvestedFundsAllowedWithdrawReasons = UnvestedFundsAllowedWithdrawReasons;
+	const MAX_VESTING_SCHEDULES: u32 = 28;
+}
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = AssetFeesAdapter;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	/// Receives the part of transaction fees not given to block authors.
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+	pub const AuthorFeeShare: Perbill = Perbill::from_percent(20);
+}
+
+/// Configure the pallet-fee-distribution in pallets/fee-distribution.
+impl pallet_fee_distribution::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type AssetId = AssetId;
+	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+	type TreasuryAccount = TreasuryAccount;
+	type DefaultAuthorShare = AuthorFeeShare;
+	type UpdateOrigin = EnsureRootOrGovernance;
+	type WeightInfo = pallet_fee_distribution::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	/// The maximum number of validators, including those of the genesis config.
+	pub const MaxValidators: u32 = 32;
+	/// Key ownership is proven for as many sessions as GRANDPA accepts equivocation reports for.
+	pub const HistoricalSessions: u32 = MaxSetIdSessionEntries::get() as u32;
+}
+
+/// Configure the pallet-validator-set in pallets/validator-set.
+impl pallet_validator_set::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddRemoveOrigin = EnsureRootOrGovernance;
+	type MinValidators = ConstU32<1>;
+	type MaxValidators = MaxValidators;
+	type Disabler = Session;
+	type HistoricalSessions = HistoricalSessions;
+	type WeightInfo = pallet_validator_set::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	/// The persistent offchain storage key the offchain worker of the template pallet reads its
+	/// values from.
+	pub const TemplateValueKey: &'static [u8] = b"template::value";
+	pub const TemplateUnsignedPriority: TransactionPriority = TransactionPriority::MAX / 2;
+}
+
+/// The accounts of the current validators, whose `tmpl` keys sign the payloads of the unsigned
+/// transactions of the offchain worker of the template pallet.
+pub struct TemplateUnsignedSigners;
+impl Contains<<Signature as Verify>::Signer> for TemplateUnsignedSigners {
+	fn contains(signer: &<Signature as Verify>::Signer) -> bool {
+		Session::validators().contains(&signer.clone().into_account())
+	}
+}
+
 /// Configure the pallet-template in pallets/template.
 impl pallet_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_template::weights::SubstrateWeight<Runtime>;
+	type MaxHistory = ConstU32<16>;
+	type AuthorityId = pallet_template::offchain::crypto::TemplateAuthId;
+	type UnsignedSigners = TemplateUnsignedSigners;
+	type ValueSource = pallet_template::offchain::LocalStorageSource<TemplateValueKey>;
+	type UnsignedInterval = ConstU32<10>;
+	type UnsignedPriority = TemplateUnsignedPriority;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -292,6 +964,64 @@ mod runtime {
 	// Include the custom logic from the pallet-template in the runtime.
 	#[runtime::pallet_index(7)]
 	pub type TemplateModule = pallet_template;
+
+	// `ValidatorSet` must come before `Session`, which reads the initial validators at genesis.
+	#[runtime::pallet_index(8)]
+	pub type ValidatorSet = pallet_validator_set;
+
+	#[runtime::pallet_index(9)]
+	pub type Session = pallet_session;
+
+	#[runtime::pallet_index(10)]
+	pub type Historical = pallet_session_historical;
+
+	#[runtime::pallet_index(11)]
+	pub type Offences = pallet_offences;
+
+	#[runtime::pallet_index(12)]
+	pub type FeeDistribution = pallet_fee_distribution;
+
+	#[runtime::pallet_index(13)]
+	pub type Utility = pallet_utility;
+
+	#[runtime::pallet_index(14)]
+	pub type Multisig = pallet_multisig;
+
+	#[runtime::pallet_index(15)]
+	pub type Proxy = pallet_proxy;
+
+	#[runtime::pallet_index(16)]
+	pub type Council = pallet_collective<Instance1>;
+
+	#[runtime::pallet_index(17)]
+	pub type TechnicalCommittee = pallet_collective<Instance2>;
+
+	#[runtime::pallet_index(18)]
+	pub type Governance = pallet_governance;
+
+	#[runtime::pallet_index(19)]
+	pub type Preimage = pallet_preimage;
+
+	#[runtime::pallet_index(20)]
+	pub type Scheduler = pallet_scheduler;
+
+	#[runtime::pallet_index(21)]
+	pub type SafeMode = pallet_safe_mode;
+
+	#[runtime::pallet_index(22)]
+	pub type TxPause = pallet_tx_pause;
+
+	#[runtime::pallet_index(23)]
+	pub type Assets = pallet_assets;
+
+	#[runtime::pallet_index(24)]
+	pub type AssetRate = pallet_asset_rate;
+
+	#[runtime::pallet_index(25)]
+	pub type AssetTxPayment = pallet_asset_tx_payment;
+
+	#[runtime::pallet_index(26)]
+	pub type Vesting = pallet_vesting;
 }
 
 /// The address format for describing accounts.
@@ -309,14 +1039,28 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	// Charges fees in the native currency or, if the transaction says so, in an asset.
+	pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
 );
 
+/// The migrations of the runtime, run by [`Executive`] on runtime upgrade.
+pub mod migrations {
+	use super::*;
+
+	/// Migrations not released yet.
+	///
+	/// Each of them checks the storage version of its pallet, so that it is a no-op once applied.
+	/// They are removed once the runtime including them is enacted on all chains.
+	pub type Unreleased = (pallet_template::migrations::v1::MigrateV0ToV1<Runtime>,);
+
+	/// Migrations run on every runtime upgrade.
+	pub type Permanent = ();
+}
+
 /// All migrations of the runtime, aside from the ones declared in the pallets.
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
-#[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (migrations::Unreleased, migrations::Permanent);
 
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic =
@@ -342,6 +1086,20 @@ mod benches {
 		[pallet_timestamp, Timestamp]
 		[pallet_sudo, Sudo]
 		[pallet_template, TemplateModule]
+		[pallet_validator_set, ValidatorSet]
+		[pallet_fee_distribution, FeeDistribution]
+		[pallet_utility, Utility]
+		[pallet_multisig, Multisig]
+		[pallet_proxy, Proxy]
+		[pallet_collective, Council]
+		[pallet_governance, Governance]
+		[pallet_preimage, Preimage]
+		[pallet_scheduler, Scheduler]
+		[pallet_safe_mode, SafeMode]
+		[pallet_tx_pause, TxPause]
+		[pallet_assets, Assets]
+		[pallet_asset_rate, AssetRate]
+		[pallet_vesting, Vesting]
 	);
 }
 
@@ -401,6 +1159,10 @@ impl_runtime_apis! {
 			tx: <Block as BlockT>::Extrinsic,
 			block_hash: <Block as BlockT>::Hash,
 		) -> TransactionValidity {
+			// Filtered calls would only fail once included: keep them out of the pool instead.
+			if !RuntimeCallFilter::contains(&tx.function) {
+				return InvalidTransaction::Call.into()
+			}
 			Executive::validate_transaction(source, tx, block_hash)
 		}
 	}
@@ -443,23 +1205,29 @@ impl_runtime_apis! {
 		}
 
 		fn submit_report_equivocation_unsigned_extrinsic(
-			_equivocation_proof: sp_consensus_grandpa::EquivocationProof<
+			equivocation_proof: sp_consensus_grandpa::EquivocationProof<
 				<Block as BlockT>::Hash,
 				NumberFor<Block>,
 			>,
-			_key_owner_proof: sp_consensus_grandpa::OpaqueKeyOwnershipProof,
+			key_owner_proof: sp_consensus_grandpa::OpaqueKeyOwnershipProof,
 		) -> Option<()> {
-			None
+			let key_owner_proof = key_owner_proof.decode()?;
+
+			Grandpa::submit_unsigned_equivocation_report(
+				equivocation_proof,
+				key_owner_proof,
+			)
 		}
 
 		fn generate_key_ownership_proof(
 			_set_id: sp_consensus_grandpa::SetId,
-			_authority_id: GrandpaId,
+			authority_id: GrandpaId,
 		) -> Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof> {
-			// NOTE: this is the only implementation possible since we've
-			// defined our key owner proof type as a bottom type (i.e. a type
-			// with no values).
-			None
+			// The set id is not needed: the historical session pallet proves membership in the
+			// session the authority is currently part of.
+			Historical::prove((sp_consensus_grandpa::KEY_TYPE, authority_id))
+				.map(|p| p.encode())
+				.map(sp_consensus_grandpa::OpaqueKeyOwnershipProof::new)
 		}
 	}
 
@@ -469,6 +1237,26 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_template_runtime_api::TemplateApi<Block, AccountId> for Runtime {
+		fn get_something() -> Option<u32> {
+			TemplateModule::something()
+		}
+
+		fn get_history(account: AccountId) -> Vec<u32> {
+			pallet_template::History::<Runtime>::get(account).into_inner()
+		}
+	}
+
+	impl pallet_validator_set_runtime_api::ValidatorSetApi<Block, AccountId> for Runtime {
+		fn validators() -> Vec<AccountId> {
+			pallet_validator_set::Validators::<Runtime>::get().into_inner()
+		}
+
+		fn disabled_validators() -> Vec<AccountId> {
+			ValidatorSet::disabled_validators()
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
@@ -585,4 +1373,29 @@ impl_runtime_apis! {
 			build_config::<RuntimeGenesisConfig>(config)
 		}
 	}
+
+	impl node_template_runtime_api::GenesisPresetsApi<Block> for Runtime {
+		fn preset_names() -> Vec<Vec<u8>> {
+			genesis_config_presets::preset_names()
+				.into_iter()
+				.map(|name| name.as_bytes().to_vec())
+				.collect()
+		}
+
+		fn get_preset(name: Vec<u8>) -> Option<Vec<u8>> {
+			core::str::from_utf8(&name).ok().and_then(genesis_config_presets::get_preset)
+		}
+
+		fn preset_chain(name: Vec<u8>) -> Option<node_template_runtime_api::PresetChain> {
+			core::str::from_utf8(&name).ok().and_then(genesis_config_presets::preset_chain)
+		}
+	}
+
+	impl node_template_runtime_api::VestingApi<Block, AccountId, Balance> for Runtime {
+		fn vesting_balances(
+			who: AccountId,
+		) -> Option<node_template_runtime_api::VestingBalances<Balance>> {
+			vesting_api::vesting_balances(&who)
+		}
+	}
 }
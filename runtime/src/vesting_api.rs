@@ -0,0 +1,34 @@
+//! A summary of the vesting of an account, exposed through
+//! `node_template_runtime_api::VestingApi`.
+
+use crate::{AccountId, Balance, Runtime, System};
+use frame_support::traits::LockIdentifier;
+use node_template_runtime_api::VestingBalances;
+use sp_runtime::traits::ConvertInto;
+
+/// The identifier of the balance lock of `pallet_vesting`.
+const VESTING_ID: LockIdentifier = *b"vesting ";
+
+/// The vesting balances of `who` at the current block, or `None` if it has no vesting schedule.
+pub fn vesting_balances(who: &AccountId) -> Option<VestingBalances<Balance>> {
+	let schedules = pallet_vesting::Vesting::<Runtime>::get(who)?;
+	let now = System::block_number();
+
+	let (total, locked) =
+		schedules.iter().fold((0, 0), |(total, locked): (Balance, Balance), s| {
+			(
+				total.saturating_add(s.locked()),
+				locked.saturating_add(s.locked_at::<ConvertInto>(now)),
+			)
+		});
+	let lock = pallet_balances::Locks::<Runtime>::get(who)
+		.iter()
+		.find(|lock| lock.id == VESTING_ID)
+		.map_or(0, |lock| lock.amount);
+
+	Some(VestingBalances {
+		locked,
+		vested: total.saturating_sub(locked),
+		claimable: lock.saturating_sub(locked),
+	})
+}
@@ -0,0 +1,194 @@
+//! Shared control of accounts: multisig, proxies and batching.
+
+mod common;
+
+use common::{account, new_test_ext, INITIAL_BALANCE};
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo};
+use node_template_runtime::{
+	Balances, BalancesCall, Multisig, Proxy, ProxyType, Runtime, RuntimeCall, RuntimeEvent,
+	RuntimeOrigin, System, SystemCall, Utility, UNIT,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie, Dave};
+use sp_runtime::DispatchError;
+
+fn transfer(to: sp_keyring::Sr25519Keyring, value: u128) -> RuntimeCall {
+	RuntimeCall::Balances(BalancesCall::transfer_keep_alive { dest: account(to).into(), value })
+}
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(SystemCall::remark { remark: b"hello".to_vec() })
+}
+
+fn do_something(something: u32) -> RuntimeCall {
+	RuntimeCall::TemplateModule(pallet_template::Call::do_something { something })
+}
+
+/// The result of the last `Proxy::proxy` call, as reported by its `ProxyExecuted` event.
+fn last_proxy_result() -> Result<(), DispatchError> {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::Proxy(pallet_proxy::Event::ProxyExecuted { result }) => Some(result),
+			_ => None,
+		})
+		.expect("a proxy call was executed")
+}
+
+#[test]
+fn batch_all_is_atomic() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Utility::batch_all(
+			RuntimeOrigin::signed(account(Alice)),
+			vec![transfer(Bob, 10 * UNIT), transfer(Charlie, 20 * UNIT)],
+		));
+		assert_eq!(Balances::free_balance(account(Bob)), INITIAL_BALANCE + 10 * UNIT);
+		assert_eq!(Balances::free_balance(account(Charlie)), INITIAL_BALANCE + 20 * UNIT);
+
+		// The second transfer cannot be paid, so the first one is rolled back.
+		let alice_balance = Balances::free_balance(account(Alice));
+		assert!(Utility::batch_all(
+			RuntimeOrigin::signed(account(Alice)),
+			vec![transfer(Dave, 10 * UNIT), transfer(Dave, alice_balance)],
+		)
+		.is_err());
+		assert_eq!(Balances::free_balance(account(Alice)), alice_balance);
+		assert_eq!(Balances::free_balance(account(Dave)), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn multisig_executes_on_threshold() {
+	new_test_ext().execute_with(|| {
+		let signatories = |except: sp_keyring::Sr25519Keyring| {
+			let mut others: Vec<_> = [Alice, Bob, Charlie]
+				.into_iter()
+				.filter(|k| *k != except)
+				.map(account)
+				.collect();
+			others.sort();
+			others
+		};
+		let mut all = vec![account(Alice), account(Bob), account(Charlie)];
+		all.sort();
+		let multisig = Multisig::multi_account_id(&all, 2);
+		assert_ok!(Balances::transfer_keep_alive(
+			RuntimeOrigin::signed(account(Alice)),
+			multisig.clone().into(),
+			100 * UNIT,
+		));
+
+		let call = transfer(Dave, 50 * UNIT);
+		let max_weight = call.get_dispatch_info().weight;
+
+		// The first approval only opens the operation and reserves a deposit.
+		assert_ok!(Multisig::as_multi(
+			RuntimeOrigin::signed(account(Alice)),
+			2,
+			signatories(Alice),
+			None,
+			Box::new(call.clone()),
+			max_weight,
+		));
+		assert_eq!(Balances::free_balance(account(Dave)), INITIAL_BALANCE);
+		assert!(Balances::reserved_balance(account(Alice)) > 0);
+
+		// The second approval reaches the threshold and dispatches the call.
+		assert_ok!(Multisig::as_multi(
+			RuntimeOrigin::signed(account(Bob)),
+			2,
+			signatories(Bob),
+			Some(Multisig::timepoint()),
+			Box::new(call),
+			max_weight,
+		));
+		assert_eq!(Balances::free_balance(account(Dave)), INITIAL_BALANCE + 50 * UNIT);
+		assert_eq!(Balances::free_balance(&multisig), 50 * UNIT);
+		assert_eq!(Balances::reserved_balance(account(Alice)), 0);
+	});
+}
+
+#[test]
+fn proxy_types_filter_calls() {
+	new_test_ext().execute_with(|| {
+		let real = account(Alice);
+		for (delegate, proxy_type) in [
+			(Bob, ProxyType::NonTransfer),
+			(Charlie, ProxyType::TemplateOnly),
+			(Dave, ProxyType::Governance),
+		] {
+			assert_ok!(Proxy::add_proxy(
+				RuntimeOrigin::signed(real.clone()),
+				account(delegate).into(),
+				proxy_type,
+				0,
+			));
+		}
+		let proxy = |delegate, call: RuntimeCall| {
+			assert_ok!(Proxy::proxy(
+				RuntimeOrigin::signed(account(delegate)),
+				real.clone().into(),
+				None,
+				Box::new(call),
+			));
+			last_proxy_result()
+		};
+		let filtered = Err(frame_system::Error::<Runtime>::CallFiltered.into());
+
+		// `NonTransfer` may do anything but move funds, even inside a batch.
+		assert_eq!(proxy(Bob, transfer(Bob, UNIT)), filtered);
+		assert_eq!(proxy(Bob, remark()), Ok(()));
+		let batch = RuntimeCall::Utility(pallet_utility::Call::batch_all {
+			calls: vec![remark(), transfer(Bob, UNIT)],
+		});
+		assert_eq!(proxy(Bob, batch), filtered);
+		assert_eq!(Balances::free_balance(account(Bob)), INITIAL_BALANCE);
+
+		// `TemplateOnly` may only call the template pallet.
+		assert_eq!(proxy(Charlie, remark()), filtered);
+		assert_eq!(proxy(Charlie, do_something(42)), Ok(()));
+		assert_eq!(pallet_template::Pallet::<Runtime>::something(), Some(42));
+
+		// `Governance` may use Alice's sudo key, but not her funds.
+		assert_eq!(proxy(Dave, transfer(Dave, UNIT)), filtered);
+		let sudo = RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(remark()) });
+		assert_eq!(proxy(Dave, sudo), Ok(()));
+	});
+}
+
+#[test]
+fn proxies_cannot_escalate() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(
+			RuntimeOrigin::signed(account(Alice)),
+			account(Bob).into(),
+			ProxyType::NonTransfer,
+			0,
+		));
+
+		// A `NonTransfer` proxy cannot add an `Any` proxy for the real account.
+		let add_any = RuntimeCall::Proxy(pallet_proxy::Call::add_proxy {
+			delegate: account(Charlie).into(),
+			proxy_type: ProxyType::Any,
+			delay: 0,
+		});
+		assert_ok!(Proxy::proxy(
+			RuntimeOrigin::signed(account(Bob)),
+			account(Alice).into(),
+			None,
+			Box::new(add_any),
+		));
+		assert_eq!(last_proxy_result(), Err(frame_system::Error::<Runtime>::CallFiltered.into()));
+
+		// Accounts that are not proxies cannot act for Alice at all.
+		assert_noop!(
+			Proxy::proxy(
+				RuntimeOrigin::signed(account(Charlie)),
+				account(Alice).into(),
+				None,
+				Box::new(remark()),
+			),
+			pallet_proxy::Error::<Runtime>::NotProxy
+		);
+	});
+}
@@ -0,0 +1,150 @@
+//! Transaction fees paid in assets.
+
+mod common;
+
+use codec::Encode;
+use common::{account, new_test_ext, INITIAL_BALANCE};
+use frame_support::{assert_ok, dispatch::GetDispatchInfo};
+use node_template_runtime::{
+	AssetId, AssetRate, Assets, AuthorFeeShare, Balances, Runtime, RuntimeCall, RuntimeEvent,
+	RuntimeOrigin, System, SystemCall, TransactionPayment, TreasuryAccount,
+};
+use pallet_asset_tx_payment::ChargeAssetTxPayment;
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_keyring::Sr25519Keyring::{Alice, Bob};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	DigestItem, FixedU128,
+};
+
+const ASSET: AssetId = 42;
+const ASSET_BALANCE: u128 = 1_000_000_000_000;
+const LEN: usize = 100;
+
+/// Create [`ASSET`] and give Bob [`ASSET_BALANCE`] of it.
+fn create_asset() {
+	assert_ok!(Assets::force_create(
+		RuntimeOrigin::root(),
+		ASSET.into(),
+		account(Alice).into(),
+		true,
+		1
+	));
+	assert_ok!(Assets::mint(
+		RuntimeOrigin::signed(account(Alice)),
+		ASSET.into(),
+		account(Bob).into(),
+		ASSET_BALANCE
+	));
+}
+
+/// Give one unit of [`ASSET`] the value of half a native unit.
+fn set_half_rate() {
+	assert_ok!(AssetRate::create(
+		RuntimeOrigin::root(),
+		Box::new(ASSET),
+		FixedU128::from_rational(1, 2)
+	));
+}
+
+fn remark() -> RuntimeCall {
+	RuntimeCall::System(SystemCall::remark { remark: vec![] })
+}
+
+#[test]
+fn fees_are_paid_in_assets_at_their_rate() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		set_half_rate();
+
+		let call = remark();
+		let info = call.get_dispatch_info();
+		let fee = TransactionPayment::compute_fee(LEN as u32, &info, 0);
+		assert!(fee > 0);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+			.pre_dispatch(&account(Bob), &call, &info, LEN)
+			.unwrap();
+		assert_eq!(Assets::balance(ASSET, account(Bob)), ASSET_BALANCE - 2 * fee);
+		assert_eq!(Balances::free_balance(account(Bob)), INITIAL_BALANCE);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			Some(pre),
+			&info,
+			&Default::default(),
+			LEN,
+			&Ok(())
+		));
+		// Without a block author, the whole fee goes to the treasury.
+		assert_eq!(Assets::balance(ASSET, TreasuryAccount::get()), 2 * fee);
+	});
+}
+
+#[test]
+fn asset_fees_are_split_and_tips_go_to_author() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+		set_half_rate();
+		// Alice, the only validator, authors the block.
+		System::deposit_log(DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(0u64).encode()));
+
+		let call = remark();
+		let info = call.get_dispatch_info();
+		let tip = 1_000;
+		let fee = TransactionPayment::compute_fee(LEN as u32, &info, 0);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(tip, Some(ASSET))
+			.pre_dispatch(&account(Bob), &call, &info, LEN)
+			.unwrap();
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			Some(pre),
+			&info,
+			&Default::default(),
+			LEN,
+			&Ok(())
+		));
+
+		// The author gets its share of the fee and the whole tip, as with native fees.
+		let (asset_fee, asset_tip) = (2 * fee, 2 * tip);
+		let author_fee = AuthorFeeShare::get() * asset_fee;
+		assert_eq!(Assets::balance(ASSET, account(Bob)), ASSET_BALANCE - asset_fee - asset_tip);
+		assert_eq!(Assets::balance(ASSET, account(Alice)), author_fee + asset_tip);
+		assert_eq!(Assets::balance(ASSET, TreasuryAccount::get()), asset_fee - author_fee);
+		System::assert_has_event(RuntimeEvent::FeeDistribution(
+			pallet_fee_distribution::Event::AssetFeesDistributed {
+				asset: ASSET,
+				author: Some(account(Alice)),
+				author_fee,
+				treasury_fee: asset_fee - author_fee,
+				tip: asset_tip,
+			},
+		));
+	});
+}
+
+#[test]
+fn only_assets_with_a_rate_pay_fees() {
+	new_test_ext().execute_with(|| {
+		create_asset();
+
+		let call = remark();
+		let info = call.get_dispatch_info();
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET))
+				.pre_dispatch(&account(Bob), &call, &info, LEN)
+				.err(),
+			Some(TransactionValidityError::Invalid(InvalidTransaction::Payment))
+		);
+		assert_eq!(Assets::balance(ASSET, account(Bob)), ASSET_BALANCE);
+
+		// Native fees are still available.
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::from(0, None).pre_dispatch(
+			&account(Bob),
+			&call,
+			&info,
+			LEN
+		));
+		assert!(Balances::free_balance(account(Bob)) < INITIAL_BALANCE);
+	});
+}
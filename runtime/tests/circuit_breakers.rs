@@ -0,0 +1,113 @@
+//! Safe mode and transaction pausing.
+
+mod common;
+
+use common::{account, new_test_ext};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Contains, OnInitialize},
+};
+use node_template_runtime::{
+	BalancesCall, Runtime, RuntimeCall, RuntimeCallFilter, RuntimeEvent, RuntimeOrigin, SafeMode,
+	SafeModeDuration, Sudo, System, SystemCall, PAUSE_WHOLE_PALLET, UNIT,
+};
+use sp_keyring::Sr25519Keyring::{Alice, Bob, Charlie};
+use sp_runtime::{traits::Dispatchable, DispatchError, DispatchResult};
+
+fn transfer() -> RuntimeCall {
+	RuntimeCall::Balances(BalancesCall::transfer_keep_alive {
+		dest: account(Charlie).into(),
+		value: UNIT,
+	})
+}
+
+fn burn() -> RuntimeCall {
+	RuntimeCall::Balances(BalancesCall::burn { value: UNIT, keep_alive: true })
+}
+
+/// Dispatch `call` through sudo, returning its result.
+fn sudo(call: RuntimeCall) -> DispatchResult {
+	assert_ok!(Sudo::sudo(RuntimeOrigin::signed(account(Alice)), Box::new(call)));
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::Sudo(pallet_sudo::Event::Sudid { sudo_result }) => Some(sudo_result),
+			_ => None,
+		})
+		.expect("sudo dispatched the call")
+}
+
+/// A call name in the format of `pallet_tx_pause`.
+fn name(pallet: &[u8], call: &[u8]) -> pallet_tx_pause::RuntimeCallNameOf<Runtime> {
+	(pallet.to_vec().try_into().unwrap(), call.to_vec().try_into().unwrap())
+}
+
+fn pause(pallet: &[u8], call: &[u8]) -> RuntimeCall {
+	RuntimeCall::TxPause(pallet_tx_pause::Call::pause { full_name: name(pallet, call) })
+}
+
+#[test]
+fn paused_calls_are_filtered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(sudo(pause(b"Balances", b"transfer_keep_alive")));
+
+		assert!(!RuntimeCallFilter::contains(&transfer()));
+		assert_noop!(
+			transfer().dispatch(RuntimeOrigin::signed(account(Bob))),
+			frame_system::Error::<Runtime>::CallFiltered
+		);
+		// Other calls of the pallet are still allowed.
+		assert!(RuntimeCallFilter::contains(&burn()));
+		assert_ok!(burn().dispatch(RuntimeOrigin::signed(account(Bob))));
+	});
+}
+
+#[test]
+fn whole_pallets_can_be_paused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(sudo(pause(b"Balances", PAUSE_WHOLE_PALLET)));
+		assert!(!RuntimeCallFilter::contains(&transfer()));
+		assert!(!RuntimeCallFilter::contains(&burn()));
+
+		// Governance cannot be paused, so that pauses can always be lifted.
+		assert_eq!(
+			sudo(pause(b"Sudo", PAUSE_WHOLE_PALLET)),
+			Err(pallet_tx_pause::Error::<Runtime>::Unpausable.into())
+		);
+		assert_eq!(
+			sudo(pause(b"Council", b"propose")),
+			Err(pallet_tx_pause::Error::<Runtime>::Unpausable.into())
+		);
+
+		assert_ok!(sudo(RuntimeCall::TxPause(pallet_tx_pause::Call::unpause {
+			ident: name(b"Balances", PAUSE_WHOLE_PALLET),
+		})));
+		assert!(RuntimeCallFilter::contains(&transfer()));
+	});
+}
+
+#[test]
+fn safe_mode_only_allows_whitelisted_calls_for_a_bounded_period() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			SafeMode::force_enter(RuntimeOrigin::signed(account(Bob))),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(sudo(RuntimeCall::SafeMode(pallet_safe_mode::Call::force_enter {})));
+		let until = 1 + SafeModeDuration::get();
+		assert_eq!(pallet_safe_mode::EnteredUntil::<Runtime>::get(), Some(until));
+
+		assert!(!RuntimeCallFilter::contains(&transfer()));
+		let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+		assert!(RuntimeCallFilter::contains(&remark));
+
+		// Safe mode is exited automatically once the period is over.
+		System::set_block_number(until);
+		SafeMode::on_initialize(until);
+		assert!(!RuntimeCallFilter::contains(&transfer()));
+		System::set_block_number(until + 1);
+		SafeMode::on_initialize(until + 1);
+		assert!(RuntimeCallFilter::contains(&transfer()));
+	});
+}
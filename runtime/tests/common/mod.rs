@@ -0,0 +1,55 @@
+//! Helpers shared by the runtime integration tests.
+
+use node_template_runtime::{
+	opaque::SessionKeys, AccountId, Balance, BuildStorage, RuntimeGenesisConfig, System,
+	TreasuryAccount, EXISTENTIAL_DEPOSIT, UNIT,
+};
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+
+/// The free balance of every endowed account at genesis.
+pub const INITIAL_BALANCE: Balance = 1_000 * UNIT;
+
+/// The account of a dev keyring member.
+pub fn account(who: Sr25519Keyring) -> AccountId {
+	who.to_account_id()
+}
+
+/// Externalities of a chain at block 1 with:
+/// - Alice as the only validator and the sudo key;
+/// - Alice to Ferdie endowed with [`INITIAL_BALANCE`], the treasury with the existential deposit;
+/// - Alice, Bob and Charlie in the council, Alice and Bob in the technical committee.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with(|_| ())
+}
+
+/// Like [`new_test_ext`], with the genesis config tweaked by `f`.
+pub fn new_test_ext_with(f: impl FnOnce(&mut RuntimeGenesisConfig)) -> sp_io::TestExternalities {
+	let alice = account(Sr25519Keyring::Alice);
+
+	let mut genesis = RuntimeGenesisConfig::default();
+	genesis.balances.balances = Sr25519Keyring::iter()
+		.take(6)
+		.map(|k| (k.to_account_id(), INITIAL_BALANCE))
+		.chain([(TreasuryAccount::get(), EXISTENTIAL_DEPOSIT)])
+		.collect();
+	genesis.validator_set.initial_validators = vec![alice.clone()];
+	genesis.session.keys = vec![(
+		alice.clone(),
+		alice.clone(),
+		SessionKeys {
+			aura: Sr25519Keyring::Alice.public().into(),
+			grandpa: Ed25519Keyring::Alice.public().into(),
+		},
+	)];
+	genesis.council.members = [Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie]
+		.map(account)
+		.to_vec();
+	genesis.technical_committee.members =
+		[Sr25519Keyring::Alice, Sr25519Keyring::Bob].map(account).to_vec();
+	genesis.sudo.key = Some(alice);
+	f(&mut genesis);
+
+	let mut ext: sp_io::TestExternalities = genesis.build_storage().unwrap().into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
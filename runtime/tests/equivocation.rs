@@ -0,0 +1,101 @@
+//! GRANDPA equivocation reports, from the key ownership proof to the removal of the offender.
+
+mod common;
+
+use codec::Decode;
+use common::{account, new_test_ext, new_test_ext_with};
+use frame_support::assert_ok;
+use node_template_runtime::{
+	opaque::{Block, SessionKeys},
+	Grandpa, Historical, HistoricalSessions, Runtime, RuntimeEvent, RuntimeOrigin, Session, System,
+	UncheckedExtrinsic, ValidatorSet,
+};
+use sp_consensus_grandpa::{runtime_decl_for_grandpa_api::GrandpaApi, EquivocationProof};
+use sp_core::{
+	offchain::{testing::TestTransactionPoolExt, TransactionPoolExt},
+	H256,
+};
+use sp_keyring::{
+	Ed25519Keyring,
+	Sr25519Keyring::{self, Alice, Bob},
+};
+use sp_runtime::traits::Dispatchable;
+
+/// A proof that `keyring` prevoted for two different blocks in round 1 of the current set.
+fn equivocation_proof(keyring: Ed25519Keyring) -> EquivocationProof<H256, u32> {
+	let set_id = Grandpa::current_set_id();
+	let signed_prevote = |target_hash| {
+		let prevote = finality_grandpa::Prevote { target_hash, target_number: 1 };
+		let message = finality_grandpa::Message::Prevote(prevote.clone());
+		let payload = sp_consensus_grandpa::localized_payload(1, set_id, &message);
+		(prevote, keyring.sign(&payload).into())
+	};
+	let (first, second) =
+		(signed_prevote(H256::repeat_byte(1)), signed_prevote(H256::repeat_byte(2)));
+	EquivocationProof::new(
+		set_id,
+		sp_consensus_grandpa::Equivocation::Prevote(finality_grandpa::Equivocation {
+			round_number: 1,
+			identity: keyring.public().into(),
+			first,
+			second,
+		}),
+	)
+}
+
+#[test]
+fn equivocating_validators_are_removed() {
+	// Alice and Bob are validators, so that Alice can be removed and disabled.
+	let mut ext = new_test_ext_with(|genesis| {
+		genesis.validator_set.initial_validators.push(account(Bob));
+		let keys = SessionKeys {
+			aura: Sr25519Keyring::Bob.public().into(),
+			grandpa: Ed25519Keyring::Bob.public().into(),
+		};
+		genesis.session.keys.push((account(Bob), account(Bob), keys));
+	});
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.execute_with(|| {
+		let key_owner_proof = <Runtime as GrandpaApi<Block>>::generate_key_ownership_proof(
+			Grandpa::current_set_id(),
+			Ed25519Keyring::Alice.public().into(),
+		)
+		.expect("Alice is a GRANDPA authority");
+		assert_eq!(
+			<Runtime as GrandpaApi<Block>>::submit_report_equivocation_unsigned_extrinsic(
+				equivocation_proof(Ed25519Keyring::Alice),
+				key_owner_proof,
+			),
+			Some(())
+		);
+
+		let transactions = std::mem::take(&mut pool_state.write().transactions);
+		let [tx] = &transactions[..] else { panic!("one report was submitted") };
+		let tx = UncheckedExtrinsic::decode(&mut &tx[..]).unwrap();
+		assert_ok!(tx.function.dispatch(RuntimeOrigin::none()));
+
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Offences(pallet_offences::Event::Offence { .. })
+		)));
+		assert!(!pallet_validator_set::Validators::<Runtime>::get().contains(&account(Alice)));
+		assert_eq!(ValidatorSet::disabled_validators(), vec![account(Alice)]);
+	});
+}
+
+#[test]
+fn historical_session_roots_are_pruned() {
+	new_test_ext().execute_with(|| {
+		let kept = HistoricalSessions::get();
+		for _ in 0..kept + 10 {
+			Session::rotate_session();
+		}
+
+		// The roots of the last `kept` sessions before the queued one are kept, older ones pruned.
+		let oldest = Session::current_index() + 1 - kept;
+		assert!(Historical::historical_root(oldest).is_some());
+		assert!(Historical::historical_root(oldest - 1).is_none());
+		assert!(Historical::historical_root(0).is_none());
+	});
+}
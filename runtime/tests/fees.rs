@@ -0,0 +1,106 @@
+//! Transaction fees in the native currency and their adjustment to congestion.
+
+mod common;
+
+use codec::Encode;
+use common::{account, new_test_ext};
+use frame_support::{
+	dispatch::{DispatchClass, GetDispatchInfo},
+	traits::OnFinalize,
+	weights::{constants::ExtrinsicBaseWeight, Weight, WeightToFee as _},
+};
+use node_template_runtime::{
+	BlockWeights, MinimumMultiplier, Runtime, RuntimeCall, SignedExtra, SignedPayload, System,
+	SystemCall, TransactionByteFee, TransactionPayment, UncheckedExtrinsic, WeightToFee, MILLIUNIT,
+};
+use pallet_transaction_payment::{Multiplier, NextFeeMultiplier};
+use sp_keyring::Sr25519Keyring::Alice;
+use sp_runtime::{generic::Era, traits::One, FixedPointNumber};
+
+const LEN: u32 = 100;
+
+/// Account `normal` weight to the normal class of the current block, finalize it and return the
+/// fee multiplier of the next one.
+fn end_block(normal: Weight) -> Multiplier {
+	System::set_block_consumed_resources(normal, 0);
+	TransactionPayment::on_finalize(System::block_number());
+	System::set_block_number(System::block_number() + 1);
+	NextFeeMultiplier::<Runtime>::get()
+}
+
+/// A remark signed by Alice.
+fn remark() -> UncheckedExtrinsic {
+	let call = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+	let extra: SignedExtra = (
+		frame_system::CheckNonZeroSender::new(),
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(Era::Immortal),
+		frame_system::CheckNonce::from(0),
+		frame_system::CheckWeight::new(),
+		pallet_asset_tx_payment::ChargeAssetTxPayment::from(0, None),
+	);
+	let signature = SignedPayload::new(call.clone(), extra.clone())
+		.unwrap()
+		.using_encoded(|payload| Alice.sign(payload));
+	UncheckedExtrinsic::new_signed(call, account(Alice).into(), signature.into(), extra)
+}
+
+#[test]
+fn base_extrinsic_costs_a_tenth_of_a_milliunit() {
+	assert_eq!(WeightToFee::weight_to_fee(&ExtrinsicBaseWeight::get()), MILLIUNIT / 10);
+}
+
+/// Full blocks are fuller than `TargetBlockFullness`, so the fees of the next ones rise.
+#[test]
+fn multiplier_rises_while_blocks_are_fuller_than_the_target() {
+	new_test_ext().execute_with(|| {
+		let normal_max = BlockWeights::get()
+			.get(DispatchClass::Normal)
+			.max_total
+			.expect("normal is limited");
+		let mut multiplier = Multiplier::one();
+		NextFeeMultiplier::<Runtime>::put(multiplier);
+		for _ in 0..10 {
+			let next = end_block(normal_max);
+			assert!(next > multiplier, "{next:?} should be above {multiplier:?}");
+			multiplier = next;
+		}
+	});
+}
+
+#[test]
+fn multiplier_never_drops_below_the_minimum() {
+	new_test_ext().execute_with(|| {
+		NextFeeMultiplier::<Runtime>::put(MinimumMultiplier::get());
+		for _ in 0..10 {
+			assert_eq!(end_block(Weight::zero()), MinimumMultiplier::get());
+		}
+	});
+}
+
+#[test]
+fn query_info_applies_the_next_fee_multiplier() {
+	new_test_ext().execute_with(|| {
+		let xt = remark();
+		let weight = xt.get_dispatch_info().weight;
+		let fee_with = |multiplier: Multiplier| {
+			NextFeeMultiplier::<Runtime>::put(multiplier);
+			TransactionPayment::query_info(xt.clone(), LEN).partial_fee
+		};
+
+		// The multiplier applies to the weight fee only, not to the base and length fees.
+		let unadjusted = fee_with(Multiplier::one());
+		assert_eq!(
+			unadjusted,
+			MILLIUNIT / 10 +
+				LEN as u128 * TransactionByteFee::get() +
+				WeightToFee::weight_to_fee(&weight)
+		);
+		assert_eq!(
+			fee_with(Multiplier::saturating_from_integer(3)),
+			unadjusted + 2 * WeightToFee::weight_to_fee(&weight)
+		);
+	});
+}
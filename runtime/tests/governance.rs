@@ -0,0 +1,149 @@
+//! Root-level actions through the council and the technical committee.
+
+mod common;
+
+use codec::Encode;
+use common::{account, new_test_ext, INITIAL_BALANCE};
+use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo};
+use node_template_runtime::{
+	Balances, BalancesCall, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, Sudo, System,
+	SystemCall, UNIT,
+};
+use sp_keyring::Sr25519Keyring::{self, Alice, Bob, Charlie, Dave};
+use sp_runtime::{
+	traits::{BlakeTwo256, Dispatchable, Hash},
+	DispatchError, DispatchResult,
+};
+
+type CouncilCollective = pallet_collective::Instance1;
+type TechnicalCollective = pallet_collective::Instance2;
+
+/// A call that requires the root origin.
+fn force_set_balance(who: Sr25519Keyring, new_free: u128) -> RuntimeCall {
+	RuntimeCall::Balances(BalancesCall::force_set_balance { who: account(who).into(), new_free })
+}
+
+fn as_root(call: RuntimeCall) -> RuntimeCall {
+	RuntimeCall::Governance(pallet_governance::Call::dispatch_as_root { call: Box::new(call) })
+}
+
+/// Propose `call` to the collective `I` with a threshold of `ayes.len()`, have all of `ayes` vote
+/// for it and close it. Returns the result of the executed proposal.
+fn motion<I: 'static>(ayes: &[Sr25519Keyring], call: RuntimeCall) -> DispatchResult
+where
+	Runtime: pallet_collective::Config<I, Proposal = RuntimeCall>,
+{
+	type Collective<I> = pallet_collective::Pallet<Runtime, I>;
+
+	let threshold = ayes.len() as u32;
+	let length_bound = call.encoded_size() as u32;
+	let weight_bound = call.get_dispatch_info().weight;
+	let hash = BlakeTwo256::hash_of(&call);
+	let index = Collective::<I>::proposal_count();
+
+	assert_ok!(Collective::<I>::propose(
+		RuntimeOrigin::signed(account(ayes[0])),
+		threshold,
+		Box::new(call),
+		length_bound,
+	));
+	if threshold > 1 {
+		for who in &ayes[1..] {
+			assert_ok!(Collective::<I>::vote(
+				RuntimeOrigin::signed(account(*who)),
+				hash,
+				index,
+				true
+			));
+		}
+		assert_ok!(Collective::<I>::close(
+			RuntimeOrigin::signed(account(ayes[0])),
+			hash,
+			index,
+			weight_bound,
+			length_bound,
+		));
+	}
+
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::Council(pallet_collective::Event::Executed { result, .. }) |
+			RuntimeEvent::TechnicalCommittee(pallet_collective::Event::Executed {
+				result, ..
+			}) => Some(result),
+			_ => None,
+		})
+		.expect("the motion was executed")
+}
+
+#[test]
+fn two_thirds_of_the_council_act_as_root() {
+	new_test_ext().execute_with(|| {
+		// A single councillor is not enough.
+		assert_eq!(
+			motion::<CouncilCollective>(&[Alice], as_root(force_set_balance(Dave, UNIT))),
+			Err(DispatchError::BadOrigin)
+		);
+		assert_eq!(Balances::free_balance(account(Dave)), INITIAL_BALANCE);
+
+		assert_ok!(motion::<CouncilCollective>(
+			&[Alice, Bob],
+			as_root(force_set_balance(Dave, UNIT))
+		));
+		assert_eq!(Balances::free_balance(account(Dave)), UNIT);
+		System::assert_has_event(
+			pallet_governance::Event::DispatchedAsRoot { result: Ok(()) }.into(),
+		);
+	});
+}
+
+#[test]
+fn governance_origin_controls_privileged_pallets() {
+	new_test_ext().execute_with(|| {
+		let add_validator = || {
+			RuntimeCall::ValidatorSet(pallet_validator_set::Call::add_validator {
+				who: account(Bob),
+			})
+		};
+
+		// The technical committee must be unanimous.
+		assert_eq!(
+			motion::<TechnicalCollective>(&[Alice], add_validator()),
+			Err(DispatchError::BadOrigin)
+		);
+		assert_ok!(motion::<TechnicalCollective>(&[Alice, Bob], add_validator()));
+		assert!(pallet_validator_set::Validators::<Runtime>::get().contains(&account(Bob)));
+
+		// Councillors cannot act alone, outside of a motion.
+		assert_noop!(
+			RuntimeCall::Governance(pallet_governance::Call::retire_sudo {})
+				.dispatch(RuntimeOrigin::signed(account(Charlie))),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn council_retires_the_sudo_key_for_good() {
+	new_test_ext().execute_with(|| {
+		let retire = RuntimeCall::Governance(pallet_governance::Call::retire_sudo {});
+		assert_ok!(motion::<CouncilCollective>(&[Alice, Bob, Charlie], retire));
+		assert_eq!(Sudo::key(), None);
+
+		// Every sudo call is now filtered, so the key cannot come back through governance either.
+		let remark = RuntimeCall::System(SystemCall::remark { remark: vec![] });
+		let sudo = RuntimeCall::Sudo(pallet_sudo::Call::sudo { call: Box::new(remark) });
+		assert_noop!(
+			sudo.dispatch(RuntimeOrigin::signed(account(Alice))),
+			frame_system::Error::<Runtime>::CallFiltered
+		);
+		let set_key = RuntimeCall::Sudo(pallet_sudo::Call::set_key { new: account(Alice).into() });
+		assert_eq!(
+			motion::<CouncilCollective>(&[Alice, Bob], as_root(set_key)),
+			Err(frame_system::Error::<Runtime>::CallFiltered.into())
+		);
+		assert_eq!(Sudo::key(), None);
+	});
+}
@@ -0,0 +1,33 @@
+//! The migrations run on runtime upgrade.
+
+mod common;
+
+use common::new_test_ext;
+use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+use node_template_runtime::{migrations, Runtime, System, TemplateModule};
+use pallet_template::{migrations::v1, SomethingInfo};
+
+#[test]
+fn genesis_is_at_the_latest_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn unreleased_migrations_run_once() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		v1::v0::Something::<Runtime>::put(42);
+
+		migrations::Unreleased::on_runtime_upgrade();
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+		let migrated = SomethingInfo { value: 42, updated_at: 1 };
+		assert_eq!(pallet_template::Something::<Runtime>::get(), Some(migrated));
+
+		// Migrations left in the runtime are no-ops once applied.
+		System::set_block_number(2);
+		migrations::Unreleased::on_runtime_upgrade();
+		assert_eq!(pallet_template::Something::<Runtime>::get(), Some(migrated));
+	});
+}
@@ -0,0 +1,97 @@
+//! Calls uploaded as preimages and enacted by the scheduler at a given block.
+
+mod common;
+
+use codec::Encode;
+use common::{account, new_test_ext, INITIAL_BALANCE};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::GetDispatchInfo,
+	traits::{OnInitialize, StorePreimage},
+};
+use node_template_runtime::{
+	Balances, BalancesCall, BlockNumber, Preimage, RuntimeCall, RuntimeOrigin, Scheduler, Sudo,
+	System, UNIT,
+};
+use sp_core::H256;
+use sp_keyring::Sr25519Keyring::{Alice, Dave};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+/// Advance to block `n`, servicing the scheduler's agenda on the way.
+fn run_to_block(n: BlockNumber) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+/// Schedule, as root, the dispatch of the preimage of `call` at block `when`.
+fn schedule_preimage(call: &RuntimeCall, when: BlockNumber) {
+	let len = call.encoded_size() as u32;
+	let dispatch = RuntimeCall::Governance(pallet_governance::Call::dispatch_preimage_as_root {
+		hash: H256(sp_io::hashing::blake2_256(&call.encode())),
+		len,
+		call_weight_witness: call.get_dispatch_info().weight,
+	});
+	let schedule = RuntimeCall::Scheduler(pallet_scheduler::Call::schedule {
+		when,
+		maybe_periodic: None,
+		priority: 0,
+		call: Box::new(dispatch),
+	});
+	assert_ok!(Sudo::sudo(RuntimeOrigin::signed(account(Alice)), Box::new(schedule)));
+}
+
+#[test]
+fn preimage_is_enacted_at_the_scheduled_block() {
+	new_test_ext().execute_with(|| {
+		let call = RuntimeCall::Balances(BalancesCall::force_set_balance {
+			who: account(Dave).into(),
+			new_free: UNIT,
+		});
+
+		// Anyone can upload the preimage, against a deposit.
+		assert_ok!(Preimage::note_preimage(RuntimeOrigin::signed(account(Dave)), call.encode()));
+		let deposit = INITIAL_BALANCE - Balances::free_balance(account(Dave));
+		assert!(deposit > 0);
+
+		schedule_preimage(&call, 5);
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(account(Dave)), INITIAL_BALANCE - deposit);
+
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(account(Dave)), UNIT);
+		System::assert_has_event(
+			pallet_governance::Event::DispatchedAsRoot { result: Ok(()) }.into(),
+		);
+	});
+}
+
+#[test]
+fn scheduling_requires_governance() {
+	new_test_ext().execute_with(|| {
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+		assert_noop!(
+			Scheduler::schedule(
+				RuntimeOrigin::signed(account(Dave)),
+				5,
+				None,
+				0,
+				Box::new(remark.clone())
+			),
+			DispatchError::BadOrigin
+		);
+
+		// Only governance can dispatch preimages as root, even when they are noted by others.
+		let hash = <Preimage as StorePreimage>::note(remark.encode().into()).unwrap();
+		assert_noop!(
+			RuntimeCall::Governance(pallet_governance::Call::dispatch_preimage_as_root {
+				hash,
+				len: remark.encoded_size() as u32,
+				call_weight_witness: remark.get_dispatch_info().weight,
+			})
+			.dispatch(RuntimeOrigin::signed(account(Dave))),
+			DispatchError::BadOrigin
+		);
+	});
+}
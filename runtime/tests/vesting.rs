@@ -0,0 +1,67 @@
+//! Vesting schedules, from genesis and through vested transfers.
+
+mod common;
+
+use common::{account, new_test_ext, new_test_ext_with, INITIAL_BALANCE};
+use frame_support::{assert_noop, assert_ok};
+use node_template_runtime::{
+	vesting_api::vesting_balances, Balances, RuntimeOrigin, System, Vesting, UNIT,
+};
+use node_template_runtime_api::VestingBalances;
+use pallet_vesting::VestingInfo;
+use sp_keyring::Sr25519Keyring::{Alice, Dave, Eve};
+use sp_runtime::TokenError;
+
+#[test]
+fn genesis_schedules_lock_balances() {
+	// Eve gets half of her balance at once, the rest over 10 blocks.
+	let liquid = INITIAL_BALANCE / 2;
+	new_test_ext_with(|genesis| genesis.vesting.vesting = vec![(account(Eve), 0, 10, liquid)])
+		.execute_with(|| {
+			let per_block = liquid / 10;
+			assert_eq!(
+				vesting_balances(&account(Eve)),
+				Some(VestingBalances {
+					locked: liquid - per_block,
+					vested: per_block,
+					claimable: per_block,
+				})
+			);
+			assert_eq!(vesting_balances(&account(Dave)), None);
+		});
+}
+
+#[test]
+fn vested_transfers_unlock_over_time() {
+	new_test_ext().execute_with(|| {
+		let schedule = VestingInfo::new(100 * UNIT, 10 * UNIT, 2);
+		assert_ok!(Vesting::vested_transfer(
+			RuntimeOrigin::signed(account(Alice)),
+			account(Dave).into(),
+			schedule
+		));
+		assert_eq!(
+			vesting_balances(&account(Dave)),
+			Some(VestingBalances { locked: 100 * UNIT, vested: 0, claimable: 0 })
+		);
+		assert_noop!(
+			Balances::transfer_allow_death(
+				RuntimeOrigin::signed(account(Dave)),
+				account(Alice).into(),
+				INITIAL_BALANCE + UNIT
+			),
+			TokenError::Frozen
+		);
+
+		System::set_block_number(5);
+		let unlocked =
+			VestingBalances { locked: 70 * UNIT, vested: 30 * UNIT, claimable: 30 * UNIT };
+		assert_eq!(vesting_balances(&account(Dave)), Some(unlocked));
+
+		assert_ok!(Vesting::vest(RuntimeOrigin::signed(account(Dave))));
+		assert_eq!(
+			vesting_balances(&account(Dave)),
+			Some(VestingBalances { locked: 70 * UNIT, vested: 30 * UNIT, claimable: 0 })
+		);
+	});
+}